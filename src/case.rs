@@ -0,0 +1,277 @@
+//! Typed API to generate cases from Rust code, without the GUI.
+//!
+//! ```no_run
+//! use lbflow_case_builder::case::*;
+//!
+//! let case = Case::new("channel_000_00")
+//!     .domain(DomainConfig {
+//!         dim: Dimensionality::D2,
+//!         grid: [200, 50, 1],
+//!         node_type_mask: NodeTypeMask::OnlyFluidNodes,
+//!     })
+//!     .momentum(MomentumConfig {
+//!         collision_operator: CollisionOperator::BGK { tau: 0.8 },
+//!         ..MomentumConfig::default()
+//!     })
+//!     .scalar(ScalarConfig {
+//!         name: "c".to_string(),
+//!         ..ScalarConfig::default()
+//!     });
+//! case.write_to("./cases/channel_000_00")?;
+//! # Ok::<(), CaseError>(())
+//! ```
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use crate::compare;
+use crate::templates::CaseTemplate;
+use crate::writer::{self, FileStatus};
+
+pub use crate::config::domain::GuiConfig as DomainConfig;
+pub use crate::config::job::{GuiConfig as JobConfig, SchedulerGui as Scheduler};
+pub use crate::config::momentum::{
+    BoundaryConditionGui as MomentumBC, FaceBC as MomentumFaceBC, GuiConfig as MomentumConfig,
+    InitialDensityGui as InitialDensity, InitialVelocityGui as InitialVelocity,
+    PatchBC as MomentumPatchBC,
+};
+pub use crate::config::scalar::{
+    BoundaryConditionGui as ScalarBC, FaceBC as ScalarFaceBC, GuiConfig as ScalarConfig,
+    InitialScalarValueGui as InitialScalarValue,
+    InnerBoundaryConditionGui as InnerBoundaryCondition, PatchBC as ScalarPatchBC,
+};
+pub use crate::config::{
+    BoundaryFaceGui as BoundaryFace, CargoGuiConfig as CargoConfig,
+    CollisionOperatorGui as CollisionOperator, CoordinatesGui as Coordinates,
+    DependencySourceGui as DependencySource, Dimensionality, FacePatchGui as FacePatch,
+    GitReferenceGui as GitReference, LtoGui as Lto, NodeTypeMaskGui as NodeTypeMask,
+    ParModeGui as ParMode, ReleaseProfileGui as ReleaseProfile, VelocitySetGui as VelocitySet,
+};
+
+/// Error returned while validating, loading or writing a case.
+#[derive(Debug)]
+pub enum CaseError {
+    /// The case name is not a valid Cargo package name.
+    InvalidName(String),
+    /// A grid dimension is zero.
+    InvalidGrid([usize; 3]),
+    /// The velocity set of a field does not match the dimensionality.
+    VelocitySetMismatch { field: String },
+    /// A scalar name is empty or used twice.
+    InvalidScalarName(String),
+    /// A boundary condition is set on a face the domain does not have.
+    InvalidBoundaryFace { field: String, face: String },
+    /// A generated file could not be produced, e.g. an invalid expression.
+    Generation(String),
+    /// Files that differ from the generated ones would be overwritten.
+    WouldOverwrite(Vec<String>),
+    /// A case or project file could not be loaded.
+    Load(String),
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+}
+
+impl fmt::Display for CaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CaseError::InvalidName(name) => write!(f, "Invalid case name \"{}\"", name),
+            CaseError::InvalidGrid(grid) => write!(f, "Invalid grid {:?}", grid),
+            CaseError::VelocitySetMismatch { field } => write!(
+                f,
+                "The velocity set of {} does not match the dimensionality",
+                field
+            ),
+            CaseError::InvalidScalarName(name) => write!(f, "Invalid scalar name \"{}\"", name),
+            CaseError::InvalidBoundaryFace { field, face } => {
+                write!(f, "{} has a boundary condition on the {} face", field, face)
+            }
+            CaseError::Generation(message) | CaseError::Load(message) => write!(f, "{}", message),
+            CaseError::WouldOverwrite(files) => {
+                write!(f, "{} would be overwritten", files.join(", "))
+            }
+            CaseError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+        }
+    }
+}
+
+impl std::error::Error for CaseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CaseError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// A case under construction, generated with [`Case::files`] or
+/// [`Case::write_to`].
+#[derive(Clone)]
+pub struct Case {
+    template: CaseTemplate,
+    overwrite: bool,
+}
+
+impl Case {
+    /// A 2D case with the default domain and momentum settings and no scalar.
+    pub fn new(name: impl Into<String>) -> Self {
+        Case {
+            template: CaseTemplate {
+                d_cfg: DomainConfig::default(),
+                m_cfg: MomentumConfig::default(),
+                s_cfg_vec: vec![],
+                c_cfg: CargoConfig {
+                    case_name: name.into(),
+                    ..CargoConfig::default()
+                },
+                j_cfg: JobConfig::default(),
+            },
+            overwrite: false,
+        }
+    }
+
+    /// Loads a case directory, a `case.json` file or a template saved by the
+    /// GUI.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, CaseError> {
+        let template = compare::load_case(path.as_ref()).map_err(CaseError::Load)?;
+        Ok(Case {
+            template,
+            overwrite: false,
+        })
+    }
+
+    pub fn name(&self) -> &str {
+        &self.template.c_cfg.case_name
+    }
+
+    pub fn rename(mut self, name: impl Into<String>) -> Self {
+        self.template.c_cfg.case_name = name.into();
+        self
+    }
+
+    pub fn domain(mut self, domain: DomainConfig) -> Self {
+        self.template.d_cfg = domain;
+        self
+    }
+
+    pub fn momentum(mut self, momentum: MomentumConfig) -> Self {
+        self.template.m_cfg = momentum;
+        self
+    }
+
+    /// Adds a passive scalar.
+    pub fn scalar(mut self, scalar: ScalarConfig) -> Self {
+        self.template.s_cfg_vec.push(scalar);
+        self
+    }
+
+    /// Sets the Cargo settings, keeping the name of the case.
+    pub fn cargo(mut self, cargo: CargoConfig) -> Self {
+        let case_name = std::mem::take(&mut self.template.c_cfg.case_name);
+        self.template.c_cfg = CargoConfig { case_name, ..cargo };
+        self
+    }
+
+    /// Sets the job script written for a cluster scheduler.
+    pub fn job(mut self, job: JobConfig) -> Self {
+        self.template.j_cfg = job;
+        self
+    }
+
+    /// Allows [`Case::write_to`] to replace files that differ from the
+    /// generated ones.
+    pub fn overwrite(mut self, overwrite: bool) -> Self {
+        self.overwrite = overwrite;
+        self
+    }
+
+    pub fn validate(&self) -> Result<(), CaseError> {
+        let case = &self.template;
+        let name = &case.c_cfg.case_name;
+        let is_valid_name = name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !is_valid_name {
+            return Err(CaseError::InvalidName(name.clone()));
+        }
+        let dim = case.d_cfg.dim;
+        let n_axes = match dim {
+            Dimensionality::D2 => 2,
+            Dimensionality::D3 => 3,
+        };
+        if case.d_cfg.grid[..n_axes].contains(&0) {
+            return Err(CaseError::InvalidGrid(case.d_cfg.grid));
+        }
+        let faces = BoundaryFace::get_faces(dim);
+        if case.m_cfg.velocity_set.get_dim() != dim {
+            return Err(CaseError::VelocitySetMismatch {
+                field: "momentum".to_string(),
+            });
+        }
+        for face_bc in &case.m_cfg.boundary_conditions {
+            if !faces.contains(&face_bc.boundary_face) {
+                return Err(CaseError::InvalidBoundaryFace {
+                    field: "momentum".to_string(),
+                    face: face_bc.boundary_face.to_literal(),
+                });
+            }
+        }
+        for (i, s_cfg) in case.s_cfg_vec.iter().enumerate() {
+            let is_duplicate = case.s_cfg_vec[..i]
+                .iter()
+                .any(|other| other.name == s_cfg.name);
+            if s_cfg.name.trim().is_empty() || is_duplicate {
+                return Err(CaseError::InvalidScalarName(s_cfg.name.clone()));
+            }
+            if s_cfg.velocity_set.get_dim() != dim {
+                return Err(CaseError::VelocitySetMismatch {
+                    field: s_cfg.name.clone(),
+                });
+            }
+            for face_bc in &s_cfg.boundary_conditions {
+                if !faces.contains(&face_bc.boundary_face) {
+                    return Err(CaseError::InvalidBoundaryFace {
+                        field: s_cfg.name.clone(),
+                        face: face_bc.boundary_face.to_literal(),
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the path, relative to the case directory, and the content of
+    /// every file of the case, `Cargo.toml` and `src/main.rs` included.
+    pub fn files(&self) -> Result<Vec<(String, String)>, CaseError> {
+        self.validate()?;
+        self.template
+            .get_case_files()
+            .map_err(CaseError::Generation)
+    }
+
+    /// Writes the case into `case_dir` and returns the written file paths.
+    pub fn write_to(&self, case_dir: impl AsRef<Path>) -> Result<Vec<String>, CaseError> {
+        let case_dir = case_dir.as_ref();
+        let files = self.files()?;
+        if !self.overwrite {
+            let modified_files = writer::compare_with_existing(case_dir, &files)
+                .into_iter()
+                .filter(|(_, status)| matches!(status, FileStatus::Modified))
+                .map(|(file_path, _)| file_path)
+                .collect::<Vec<String>>();
+            if !modified_files.is_empty() {
+                return Err(CaseError::WouldOverwrite(modified_files));
+            }
+        }
+        writer::write_case_files(case_dir, &files).map_err(|source| CaseError::Io {
+            path: case_dir.to_path_buf(),
+            source,
+        })?;
+        Ok(files.into_iter().map(|(file_path, _)| file_path).collect())
+    }
+}
@@ -0,0 +1,99 @@
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::compare;
+use crate::metadata::{self, CaseMetadata};
+use crate::templates::CaseTemplate;
+use crate::writer;
+
+const SKIPPED_DIR_NAMES: [&str; 2] = ["target", ".git"];
+
+pub(crate) struct CaseEntry {
+    pub(crate) name: String,
+    pub(crate) case: Result<CaseTemplate, String>,
+    pub(crate) modified: Option<SystemTime>,
+}
+
+fn get_last_modification(dir: &Path) -> Option<SystemTime> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| entry.metadata().ok()?.modified().ok())
+        .max()
+}
+
+/// Lists the cases of `parent_dir`, with their configuration read from
+/// `case.json` when the case was generated with it.
+pub(crate) fn scan_cases(parent_dir: &Path) -> Result<Vec<CaseEntry>, String> {
+    let entries = writer::get_case_dir_names(parent_dir)?
+        .into_iter()
+        .map(|name| {
+            let case_dir = parent_dir.join(&name);
+            CaseEntry {
+                case: compare::load_case(&case_dir),
+                modified: get_last_modification(&case_dir),
+                name,
+            }
+        })
+        .collect();
+    Ok(entries)
+}
+
+fn copy_dir(source: &Path, destination: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(destination)?;
+    for entry in std::fs::read_dir(source)? {
+        let entry = entry?;
+        let file_name = entry.file_name();
+        let path = entry.path();
+        if path.is_dir() {
+            if SKIPPED_DIR_NAMES.iter().any(|name| file_name == *name) {
+                continue;
+            }
+            copy_dir(&path, &destination.join(&file_name))?;
+        } else {
+            std::fs::copy(&path, destination.join(&file_name))?;
+        }
+    }
+    Ok(())
+}
+
+/// Copies a case, without its build directory, and renames the package and
+/// the metadata of the copy.
+pub(crate) fn duplicate_case(
+    parent_dir: &Path,
+    name: &str,
+    new_name: &str,
+) -> Result<PathBuf, String> {
+    let new_name = new_name.trim();
+    if new_name.is_empty() {
+        return Err("The new case name is empty".to_string());
+    }
+    let source = parent_dir.join(name);
+    let destination = parent_dir.join(new_name);
+    if destination.exists() {
+        return Err(format!("{} already exists", destination.display()));
+    }
+    let mut case = compare::load_case(&source)?;
+    case.c_cfg.case_name = new_name.to_string();
+    copy_dir(&source, &destination).map_err(|e| e.to_string())?;
+    let mut files = vec![("Cargo.toml".to_string(), case.c_cfg.get_cargo_toml())];
+    files.extend(CaseMetadata::new(case).get_files()?);
+    writer::write_case_files(&destination, &files).map_err(|e| e.to_string())?;
+    Ok(destination)
+}
+
+/// Deletes a case directory. Only directories holding a generated case
+/// (a package manifest and a `case.json`) are deleted.
+pub(crate) fn delete_case(parent_dir: &Path, name: &str) -> Result<(), String> {
+    if !writer::get_case_dir_names(parent_dir)?
+        .iter()
+        .any(|case_name| case_name == name)
+    {
+        return Err(format!("{} is not a case", name));
+    }
+    let case_dir = parent_dir.join(name);
+    if !case_dir.join(metadata::CASE_JSON_FILE_PATH).is_file() {
+        return Err(format!("{} was not generated by the case builder", name));
+    }
+    std::fs::remove_dir_all(case_dir).map_err(|e| e.to_string())
+}
@@ -0,0 +1,57 @@
+use std::path::Path;
+
+use crate::case::{Case, CaseError};
+use crate::compare;
+
+const USAGE: &str = "Usage:
+    lbflow_case_builder compare <case or project file> <case or project file>
+    lbflow_case_builder generate <case or project file> <case directory> [--force]";
+
+/// Runs the command given on the command line, if any. Returns `None` when
+/// the GUI should be started instead.
+pub fn run_command(args: &[String]) -> Option<Result<String, String>> {
+    let (command, args) = args.split_first()?;
+    let result = match command.as_str() {
+        "compare" => run_compare(args),
+        "generate" => run_generate(args),
+        "help" | "--help" | "-h" => Ok(format!("{}\n", USAGE)),
+        _ => Err(format!("Unknown command \"{}\"\n{}", command, USAGE)),
+    };
+    Some(result)
+}
+
+fn run_compare(args: &[String]) -> Result<String, String> {
+    let [left_path, right_path] = args else {
+        return Err(USAGE.to_string());
+    };
+    let left = compare::load_case(Path::new(left_path))?;
+    let right = compare::load_case(Path::new(right_path))?;
+    let diffs = compare::compare_cases(&left, &right)?;
+    Ok(compare::get_diff_content(&diffs))
+}
+
+fn run_generate(args: &[String]) -> Result<String, String> {
+    let (force, args) = match args {
+        [args @ .., last] if last == "--force" => (true, args),
+        args => (false, args),
+    };
+    let [case_path, case_dir] = args else {
+        return Err(USAGE.to_string());
+    };
+    let case_dir = Path::new(case_dir);
+    let mut case = Case::from_file(case_path)
+        .map_err(|e| e.to_string())?
+        .overwrite(force);
+    if let Some(case_name) = case_dir.file_name() {
+        case = case.rename(case_name.to_string_lossy());
+    }
+    match case.write_to(case_dir) {
+        Ok(_) => Ok(format!(
+            "Case {} written to {}\n",
+            case.name(),
+            case_dir.display()
+        )),
+        Err(e @ CaseError::WouldOverwrite(_)) => Err(format!("{}, use --force", e)),
+        Err(e) => Err(e.to_string()),
+    }
+}
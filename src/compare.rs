@@ -0,0 +1,432 @@
+use serde_json::{Map, Value};
+use std::path::Path;
+
+use crate::config::CollisionOperatorGui;
+use crate::metadata::{self, CaseMetadata, LATTICE_SOUND_SPEED};
+use crate::templates::{CaseTemplate, UserTemplate};
+
+pub(crate) struct FieldDiff {
+    pub(crate) field: String,
+    pub(crate) left: Option<String>,
+    pub(crate) right: Option<String>,
+}
+
+/// Loads a case from a generated case directory (its `case.json`), a
+/// `case.json` file, a user template or a bare case configuration.
+pub(crate) fn load_case(path: &Path) -> Result<CaseTemplate, String> {
+    let file_path = if path.is_dir() {
+        path.join(metadata::CASE_JSON_FILE_PATH)
+    } else {
+        path.to_path_buf()
+    };
+    let content = std::fs::read_to_string(&file_path)
+        .map_err(|e| format!("Could not read {}: {}", file_path.display(), e))?;
+    if let Ok(case_metadata) = serde_json::from_str::<CaseMetadata>(&content) {
+        return Ok(case_metadata.case);
+    }
+    if let Ok(user_template) = serde_json::from_str::<UserTemplate>(&content) {
+        return Ok(user_template.case);
+    }
+    serde_json::from_str::<CaseTemplate>(&content)
+        .map_err(|e| format!("{} is not a case: {}", file_path.display(), e))
+}
+
+/// Turns an array of objects into an object keyed by one of their fields,
+/// so that elements are matched by name rather than by position.
+fn key_array_by(value: &mut Value, key: &str) {
+    let Value::Array(elements) = value else {
+        return;
+    };
+    let mut keyed = Map::new();
+    for (i, mut element) in elements.drain(..).enumerate() {
+        let name = match element
+            .as_object_mut()
+            .and_then(|object| object.remove(key))
+        {
+            Some(Value::String(name)) => name,
+            Some(name) => name.to_string(),
+            None => i.to_string(),
+        };
+        keyed.insert(name, element);
+    }
+    *value = Value::Object(keyed);
+}
+
+/// Rounds a converted value to 10 significant digits, so that the rounding
+/// errors of the conversion do not show up as differences.
+fn get_physical_value(value: f64) -> Value {
+    Value::from(format!("{:.9e}", value).parse::<f64>().unwrap_or(value))
+}
+
+/// Lattice to physical unit conversions of a case.
+struct UnitConversions {
+    velocity: f64,
+    diffusivity: f64,
+    density: f64,
+    reference_pressure: f64,
+}
+
+impl UnitConversions {
+    fn new(case: &CaseTemplate) -> Self {
+        let m_cfg = &case.m_cfg;
+        UnitConversions {
+            velocity: m_cfg.delta_x / m_cfg.delta_t,
+            diffusivity: m_cfg.delta_x * m_cfg.delta_x / m_cfg.delta_t,
+            density: m_cfg.physical_density,
+            reference_pressure: m_cfg.reference_pressure,
+        }
+    }
+
+    /// Pressure of a lattice density, from p = p_ref + cs^2 (rho - 1) in
+    /// lattice units.
+    fn get_pressure(&self, rho: f64) -> f64 {
+        self.reference_pressure
+            + LATTICE_SOUND_SPEED
+                * LATTICE_SOUND_SPEED
+                * (rho - 1.0)
+                * self.density
+                * self.velocity
+                * self.velocity
+    }
+
+    /// Adds, next to the lattice density `rho` and velocity `ux`, `uy`, `uz`
+    /// fields of an object, their values in physical units.
+    fn insert_physical_values(&self, object: &mut Map<String, Value>) {
+        if let Some(rho) = object.get("rho").and_then(Value::as_f64) {
+            object.insert(
+                "rho (kg/m^3)".to_string(),
+                get_physical_value(rho * self.density),
+            );
+            object.insert(
+                "p (Pa)".to_string(),
+                get_physical_value(self.get_pressure(rho)),
+            );
+        }
+        for component in ["ux", "uy", "uz"] {
+            if let Some(u) = object.get(component).and_then(Value::as_f64) {
+                object.insert(
+                    format!("{} (m/s)", component),
+                    get_physical_value(u * self.velocity),
+                );
+            }
+        }
+    }
+
+    /// Adds the physical viscosity or diffusivity to the parameters object
+    /// of a serialized collision operator.
+    fn insert_transport_coefficient(
+        &self,
+        collision_operator_value: &mut Value,
+        collision_operator: &CollisionOperatorGui,
+        name: &str,
+    ) {
+        if let Some(parameters) = collision_operator_value
+            .as_object_mut()
+            .and_then(|object| object.values_mut().next())
+            .and_then(Value::as_object_mut)
+            && let Some(lattice_coefficient) =
+                metadata::get_lattice_transport_coefficient(collision_operator)
+        {
+            parameters.insert(
+                format!("{} (m^2/s)", name),
+                get_physical_value(lattice_coefficient * self.diffusivity),
+            );
+        }
+    }
+}
+
+/// Serializes a case for comparison: arrays of faces and scalars are keyed
+/// by face and name, every lattice value with a physical counterpart gets a
+/// sibling field in physical units, and the derived numbers are added under
+/// `derived`.
+fn get_case_value(case: &CaseTemplate) -> Result<Value, String> {
+    let mut value = serde_json::to_value(case).map_err(|e| e.to_string())?;
+    let conversions = UnitConversions::new(case);
+    if let Some(Value::Object(m_cfg)) = value.get_mut("m_cfg") {
+        m_cfg.insert(
+            "velocity unit (m/s)".to_string(),
+            get_physical_value(conversions.velocity),
+        );
+        m_cfg.insert(
+            "viscosity unit (m^2/s)".to_string(),
+            get_physical_value(conversions.diffusivity),
+        );
+    }
+    if let Some(collision_operator) = value.pointer_mut("/m_cfg/collision_operator") {
+        conversions.insert_transport_coefficient(
+            collision_operator,
+            &case.m_cfg.collision_operator,
+            "viscosity",
+        );
+    }
+    for pointer in ["/m_cfg/initial_density", "/m_cfg/initial_velocity"] {
+        if let Some(Value::Object(uniform)) = value
+            .pointer_mut(pointer)
+            .and_then(|initial| initial.get_mut("Uniform"))
+        {
+            conversions.insert_physical_values(uniform);
+        }
+    }
+    if let Some(boundary_conditions) = value.pointer_mut("/m_cfg/boundary_conditions") {
+        key_array_by(boundary_conditions, "boundary_face");
+    }
+    for pointer in ["/m_cfg/boundary_conditions", "/m_cfg/boundary_patches"] {
+        let Some(boundary_conditions) = value.pointer_mut(pointer) else {
+            continue;
+        };
+        let boundary_conditions = match boundary_conditions {
+            Value::Object(object) => object.values_mut().collect::<Vec<_>>(),
+            Value::Array(elements) => elements.iter_mut().collect(),
+            _ => vec![],
+        };
+        for boundary_condition in boundary_conditions
+            .into_iter()
+            .filter_map(|bc| bc.get_mut("boundary_condition"))
+            .filter_map(Value::as_object_mut)
+            .flat_map(|object| object.values_mut())
+            .filter_map(Value::as_object_mut)
+        {
+            conversions.insert_physical_values(boundary_condition);
+        }
+    }
+    if let Some(s_cfg_vec) = value.get_mut("s_cfg_vec") {
+        if let Value::Array(elements) = s_cfg_vec {
+            for (s_cfg_value, s_cfg) in elements.iter_mut().zip(&case.s_cfg_vec) {
+                if let Some(collision_operator) = s_cfg_value.get_mut("collision_operator") {
+                    conversions.insert_transport_coefficient(
+                        collision_operator,
+                        &s_cfg.collision_operator,
+                        "diffusivity",
+                    );
+                }
+            }
+        }
+        key_array_by(s_cfg_vec, "name");
+        for s_cfg in s_cfg_vec
+            .as_object_mut()
+            .into_iter()
+            .flat_map(|o| o.values_mut())
+        {
+            if let Some(boundary_conditions) = s_cfg.get_mut("boundary_conditions") {
+                key_array_by(boundary_conditions, "boundary_face");
+            }
+        }
+    }
+    let mut derived =
+        serde_json::to_value(metadata::get_derived_quantities(case)).map_err(|e| e.to_string())?;
+    if let Some(scalars) = derived.get_mut("scalars") {
+        key_array_by(scalars, "name");
+    }
+    if let Value::Object(object) = &mut value {
+        object.insert("derived".to_string(), derived);
+    }
+    Ok(value)
+}
+
+fn flatten(value: &Value, path: &str, fields: &mut Vec<(String, String)>) {
+    let join = |key: &str| {
+        if path.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", path, key)
+        }
+    };
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object {
+                flatten(value, &join(key), fields);
+            }
+        }
+        Value::Array(elements) => {
+            for (i, element) in elements.iter().enumerate() {
+                flatten(element, &format!("{}[{}]", path, i), fields);
+            }
+        }
+        Value::String(string) => fields.push((path.to_string(), string.clone())),
+        value => fields.push((path.to_string(), value.to_string())),
+    }
+}
+
+/// Lists every parameter that differs between the two cases. Derived numbers
+/// are compared under `derived`, and lattice values also in physical units.
+pub(crate) fn compare_cases(
+    left: &CaseTemplate,
+    right: &CaseTemplate,
+) -> Result<Vec<FieldDiff>, String> {
+    let mut left_fields = vec![];
+    flatten(&get_case_value(left)?, "", &mut left_fields);
+    let mut right_fields = vec![];
+    flatten(&get_case_value(right)?, "", &mut right_fields);
+    let mut diffs = vec![];
+    for (field, left_value) in &left_fields {
+        let right_value = right_fields
+            .iter()
+            .find(|(right_field, _)| right_field == field)
+            .map(|(_, value)| value.clone());
+        if right_value.as_ref() != Some(left_value) {
+            diffs.push(FieldDiff {
+                field: field.clone(),
+                left: Some(left_value.clone()),
+                right: right_value,
+            });
+        }
+    }
+    for (field, right_value) in &right_fields {
+        if !left_fields
+            .iter()
+            .any(|(left_field, _)| left_field == field)
+        {
+            diffs.push(FieldDiff {
+                field: field.clone(),
+                left: None,
+                right: Some(right_value.clone()),
+            });
+        }
+    }
+    diffs.sort_by(|a, b| a.field.cmp(&b.field));
+    Ok(diffs)
+}
+
+pub(crate) fn get_diff_content(diffs: &[FieldDiff]) -> String {
+    if diffs.is_empty() {
+        return "No differences\n".to_string();
+    }
+    let mut content = String::new();
+    for diff in diffs {
+        content.push_str(&format!(
+            "{}: {} -> {}\n",
+            diff.field,
+            diff.left.as_deref().unwrap_or("-"),
+            diff.right.as_deref().unwrap_or("-")
+        ));
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{compare_cases, flatten, key_array_by};
+    use crate::config::{BoundaryFaceGui, CargoGuiConfig, domain, job, momentum, scalar};
+    use crate::templates::CaseTemplate;
+
+    fn get_case(scalar_names: &[&str]) -> CaseTemplate {
+        CaseTemplate {
+            d_cfg: domain::GuiConfig::default(),
+            m_cfg: momentum::GuiConfig::default(),
+            s_cfg_vec: scalar_names
+                .iter()
+                .map(|name| scalar::GuiConfig {
+                    name: name.to_string(),
+                    ..scalar::GuiConfig::default()
+                })
+                .collect(),
+            c_cfg: CargoGuiConfig::default(),
+            j_cfg: job::GuiConfig::default(),
+        }
+    }
+
+    fn get_fields(value: &serde_json::Value) -> Vec<(String, String)> {
+        let mut fields = vec![];
+        flatten(value, "", &mut fields);
+        fields
+    }
+
+    fn get_field_names(diffs: &[super::FieldDiff]) -> Vec<&str> {
+        diffs.iter().map(|diff| diff.field.as_str()).collect()
+    }
+
+    #[test]
+    fn flattening_nested_arrays() {
+        let value = json!({"a": {"b": [1, [2.5, "x"], {"c": null}]}, "d": "text"});
+        let fields = get_fields(&value);
+        let expected = [
+            ("a.b[0]", "1"),
+            ("a.b[1][0]", "2.5"),
+            ("a.b[1][1]", "x"),
+            ("a.b[2].c", "null"),
+            ("d", "text"),
+        ];
+        assert_eq!(fields.len(), expected.len());
+        for ((field, value), (expected_field, expected_value)) in fields.iter().zip(expected) {
+            assert_eq!(field, expected_field);
+            assert_eq!(value, expected_value);
+        }
+    }
+
+    #[test]
+    fn keying_arrays_by_name() {
+        let mut value = json!([{"name": "c", "tau": 0.6}, {"name": 7}, {"tau": 0.8}, 3]);
+        key_array_by(&mut value, "name");
+        assert_eq!(
+            value,
+            json!({"c": {"tau": 0.6}, "7": {}, "2": {"tau": 0.8}, "3": 3})
+        );
+    }
+
+    #[test]
+    fn scalars_and_faces_are_matched_by_name() {
+        let left = get_case(&["c", "t"]);
+        let mut right = left.clone();
+        right.s_cfg_vec.reverse();
+        right.s_cfg_vec[0].boundary_conditions.reverse();
+        assert!(compare_cases(&left, &right).unwrap().is_empty());
+        right.s_cfg_vec[0].boundary_conditions[0].boundary_condition =
+            scalar::BoundaryConditionGui::ZerothOrderNoFlux;
+        let diffs = compare_cases(&left, &right).unwrap();
+        assert_eq!(
+            get_field_names(&diffs),
+            ["s_cfg_vec.t.boundary_conditions.North.boundary_condition"]
+        );
+        assert_eq!(diffs[0].left.as_deref(), Some("AntiBBNoFlux"));
+        assert_eq!(diffs[0].right.as_deref(), Some("ZerothOrderNoFlux"));
+    }
+
+    #[test]
+    fn boundary_values_are_compared_in_physical_units_too() {
+        let get_west_pressure_case = |rho| {
+            let mut case = get_case(&[]);
+            let west = case
+                .m_cfg
+                .boundary_conditions
+                .iter_mut()
+                .find(|face_bc| face_bc.boundary_face == BoundaryFaceGui::West)
+                .unwrap();
+            west.boundary_condition = momentum::BoundaryConditionGui::AntiBounceBack { rho };
+            case
+        };
+        let left = get_west_pressure_case(1.001);
+        let right = get_west_pressure_case(1.002);
+        let diffs = compare_cases(&left, &right).unwrap();
+        let prefix = "m_cfg.boundary_conditions.West.boundary_condition.AntiBounceBack.";
+        for name in ["rho", "rho (kg/m^3)", "p (Pa)"] {
+            let field = format!("{}{}", prefix, name);
+            assert!(
+                get_field_names(&diffs).contains(&field.as_str()),
+                "{}",
+                field
+            );
+        }
+        assert!(
+            diffs
+                .iter()
+                .all(|diff| diff.left.is_some() && diff.right.is_some())
+        );
+    }
+
+    #[test]
+    fn missing_fields_are_reported_on_one_side() {
+        let left = get_case(&["c"]);
+        let mut right = left.clone();
+        right.s_cfg_vec.clear();
+        let diffs = compare_cases(&left, &right).unwrap();
+        assert!(!diffs.is_empty());
+        assert!(
+            diffs
+                .iter()
+                .filter(|diff| diff.field.starts_with("s_cfg_vec.c."))
+                .all(|diff| diff.left.is_some() && diff.right.is_none())
+        );
+    }
+}
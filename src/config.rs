@@ -1,9 +1,15 @@
 pub(crate) mod domain;
+pub(crate) mod job;
 pub(crate) mod momentum;
 pub(crate) mod scalar;
 
+#[cfg(feature = "gui")]
 use eframe::egui;
+use serde::{Deserialize, Serialize};
 
+use crate::expression::Expression;
+
+#[cfg(feature = "gui")]
 pub(crate) trait LatticeGuiConfig {
     fn get_velocity_set_gui(&mut self) -> &mut VelocitySetGui;
 
@@ -101,14 +107,23 @@ pub(crate) trait LatticeGuiConfig {
     }
 }
 
-#[derive(PartialEq, Eq, Clone, Copy)]
-pub(crate) enum Dimensionality {
+#[derive(PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum Dimensionality {
     D2,
     D3,
 }
 
-#[derive(PartialEq, Eq)]
-pub(crate) enum VelocitySetGui {
+impl Dimensionality {
+    pub(crate) fn get_n_axes(self) -> usize {
+        match self {
+            Dimensionality::D2 => 2,
+            Dimensionality::D3 => 3,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub enum VelocitySetGui {
     D2Q5,
     D2Q9,
     D3Q15,
@@ -116,15 +131,73 @@ pub(crate) enum VelocitySetGui {
     D3Q27,
 }
 
-#[derive(PartialEq)]
-pub(crate) enum CollisionOperatorGui {
+impl VelocitySetGui {
+    pub(crate) fn get_label(&self) -> &'static str {
+        match self {
+            VelocitySetGui::D2Q5 => "D2Q5",
+            VelocitySetGui::D2Q9 => "D2Q9",
+            VelocitySetGui::D3Q15 => "D3Q15",
+            VelocitySetGui::D3Q19 => "D3Q19",
+            VelocitySetGui::D3Q27 => "D3Q27",
+        }
+    }
+
+    pub(crate) fn get_dim(&self) -> Dimensionality {
+        match self {
+            VelocitySetGui::D2Q5 | VelocitySetGui::D2Q9 => Dimensionality::D2,
+            VelocitySetGui::D3Q15 | VelocitySetGui::D3Q19 | VelocitySetGui::D3Q27 => {
+                Dimensionality::D3
+            }
+        }
+    }
+
+    #[cfg(feature = "gui")]
+    pub(crate) fn get_velocities(&self) -> Vec<[isize; 3]> {
+        let mut velocities = vec![];
+        for cz in -1_isize..=1 {
+            for cy in -1_isize..=1 {
+                for cx in -1_isize..=1 {
+                    let n_non_zero = [cx, cy, cz].iter().filter(|c| **c != 0).count();
+                    let is_member = match self {
+                        VelocitySetGui::D2Q5 => cz == 0 && n_non_zero <= 1,
+                        VelocitySetGui::D2Q9 => cz == 0,
+                        VelocitySetGui::D3Q15 => n_non_zero != 2,
+                        VelocitySetGui::D3Q19 => n_non_zero <= 2,
+                        VelocitySetGui::D3Q27 => true,
+                    };
+                    if is_member {
+                        velocities.push([cx, cy, cz]);
+                    }
+                }
+            }
+        }
+        velocities
+    }
+}
+
+#[allow(clippy::upper_case_acronyms)]
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+pub enum CollisionOperatorGui {
     BGK { tau: f64 },
     TRT { omega_plus: f64, omega_minus: f64 },
     MRT,
 }
 
-#[derive(PartialEq, Clone, Copy)]
-pub(crate) enum BoundaryFaceGui {
+impl CollisionOperatorGui {
+    pub(crate) fn get_label(&self) -> String {
+        match self {
+            CollisionOperatorGui::BGK { tau } => format!("BGK (tau = {})", tau),
+            CollisionOperatorGui::TRT {
+                omega_plus,
+                omega_minus,
+            } => format!("TRT (omega+ = {}, omega- = {})", omega_plus, omega_minus),
+            CollisionOperatorGui::MRT => "MRT".to_string(),
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Copy, Serialize, Deserialize)]
+pub enum BoundaryFaceGui {
     West,
     East,
     South,
@@ -134,7 +207,49 @@ pub(crate) enum BoundaryFaceGui {
 }
 
 impl BoundaryFaceGui {
-    pub(crate) fn to_literal(&self) -> String {
+    pub(crate) fn get_axis(self) -> usize {
+        match self {
+            BoundaryFaceGui::West | BoundaryFaceGui::East => 0,
+            BoundaryFaceGui::South | BoundaryFaceGui::North => 1,
+            BoundaryFaceGui::Bottom | BoundaryFaceGui::Top => 2,
+        }
+    }
+
+    #[cfg(feature = "gui")]
+    pub(crate) fn from_axis(axis: usize, is_min_side: bool) -> Self {
+        match (axis, is_min_side) {
+            (0, true) => BoundaryFaceGui::West,
+            (0, false) => BoundaryFaceGui::East,
+            (1, true) => BoundaryFaceGui::South,
+            (1, false) => BoundaryFaceGui::North,
+            (_, true) => BoundaryFaceGui::Bottom,
+            (_, false) => BoundaryFaceGui::Top,
+        }
+    }
+
+    pub(crate) fn is_min_side(self) -> bool {
+        matches!(
+            self,
+            BoundaryFaceGui::West | BoundaryFaceGui::South | BoundaryFaceGui::Bottom
+        )
+    }
+
+    pub(crate) fn get_faces(dim: Dimensionality) -> Vec<BoundaryFaceGui> {
+        let faces = vec![
+            BoundaryFaceGui::West,
+            BoundaryFaceGui::East,
+            BoundaryFaceGui::South,
+            BoundaryFaceGui::North,
+            BoundaryFaceGui::Bottom,
+            BoundaryFaceGui::Top,
+        ];
+        match dim {
+            Dimensionality::D2 => faces[..4].to_vec(),
+            Dimensionality::D3 => faces,
+        }
+    }
+
+    pub(crate) fn to_literal(self) -> String {
         match self {
             BoundaryFaceGui::West => "West".to_string(),
             BoundaryFaceGui::East => "East".to_string(),
@@ -146,22 +261,339 @@ impl BoundaryFaceGui {
     }
 }
 
-#[derive(PartialEq, Eq)]
-pub(crate) enum NodeTypeMaskGui {
+/// Part of a boundary face, given as half-open node ranges along the two
+/// tangential axes of the face in increasing axis order. In 2D the second
+/// range (along z) is ignored.
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+pub struct FacePatchGui {
+    pub boundary_face: BoundaryFaceGui,
+    pub ranges: [[usize; 2]; 2],
+}
+
+impl FacePatchGui {
+    #[cfg(feature = "gui")]
+    /// A patch covering the whole face.
+    pub(crate) fn new(boundary_face: BoundaryFaceGui, grid: [usize; 3]) -> Self {
+        let [a, b] = get_tangential_axes(boundary_face);
+        FacePatchGui {
+            boundary_face,
+            ranges: [[0, grid[a]], [0, grid[b]]],
+        }
+    }
+
+    /// Whether the boundary node at `coords` lies in the patch. The coordinate
+    /// along the face normal is not checked.
+    pub(crate) fn contains(&self, coords: [usize; 3], dim: Dimensionality) -> bool {
+        get_tangential_axes(self.boundary_face)
+            .iter()
+            .zip(&self.ranges)
+            .filter(|(axis, _)| **axis < dim.get_n_axes())
+            .all(|(axis, [start, end])| (*start..*end).contains(&coords[*axis]))
+    }
+
+    pub(crate) fn validate(&self, grid: [usize; 3], dim: Dimensionality) -> Result<(), String> {
+        if self.boundary_face.get_axis() >= dim.get_n_axes() {
+            return Err(format!(
+                "A patch is on the {} face, which a 2D domain does not have",
+                self.boundary_face.to_literal()
+            ));
+        }
+        for (axis, [start, end]) in get_tangential_axes(self.boundary_face)
+            .iter()
+            .zip(&self.ranges)
+        {
+            if *axis < dim.get_n_axes() && (start >= end || *end > grid[*axis]) {
+                return Err(format!(
+                    "The patch {}..{} along {} on the {} face is empty or leaves the grid",
+                    start,
+                    end,
+                    AXIS_NAMES[*axis],
+                    self.boundary_face.to_literal()
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Why patches are limited: lbflow_soa takes one condition per face, and
+/// the rest of a patched face is made of solid nodes shared by all fields.
+#[cfg(feature = "gui")]
+pub(crate) const PATCH_LIMITATION_NOTE: &str = "lbflow_soa takes one condition per face: the patches of a face share one condition, and the rest of the face becomes solid wall nodes for every field, so the face itself must be No-slip for the momentum and no-flux for every scalar.";
+
+pub(crate) const AXIS_NAMES: [&str; 3] = ["x", "y", "z"];
+
+pub(crate) fn get_tangential_axes(boundary_face: BoundaryFaceGui) -> [usize; 2] {
+    match boundary_face.get_axis() {
+        0 => [1, 2],
+        1 => [0, 2],
+        _ => [0, 1],
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum CoordinatesGui {
+    Lattice,
+    Physical,
+}
+
+pub(crate) const FIELD_EXPRESSION_VARIABLES: [&str; 9] =
+    ["x", "y", "z", "nx", "ny", "nz", "lx", "ly", "lz"];
+
+/// Evaluates the expressions on every node of the grid, x running fastest,
+/// and returns one row of values per node.
+pub(crate) fn evaluate_expressions_on_grid(
+    expressions: &[&str],
+    grid: [usize; 3],
+    coordinates: CoordinatesGui,
+    delta_x: f64,
+) -> Result<Vec<Vec<f64>>, String> {
+    let expressions = expressions
+        .iter()
+        .map(|expression| {
+            Expression::parse(expression, &FIELD_EXPRESSION_VARIABLES)
+                .map_err(|e| format!("{} in \"{}\"", e, expression))
+        })
+        .collect::<Result<Vec<Expression>, String>>()?;
+    let scale = match coordinates {
+        CoordinatesGui::Lattice => 1.0,
+        CoordinatesGui::Physical => delta_x,
+    };
+    let mut values = [
+        0.0,
+        0.0,
+        0.0,
+        grid[0] as f64,
+        grid[1] as f64,
+        grid[2] as f64,
+        grid[0] as f64 * scale,
+        grid[1] as f64 * scale,
+        grid[2] as f64 * scale,
+    ];
+    let mut rows = Vec::with_capacity(grid[0] * grid[1] * grid[2]);
+    for z in 0..grid[2] {
+        for y in 0..grid[1] {
+            for x in 0..grid[0] {
+                values[0] = x as f64 * scale;
+                values[1] = y as f64 * scale;
+                values[2] = z as f64 * scale;
+                let row = expressions
+                    .iter()
+                    .map(|expression| expression.evaluate(&values))
+                    .collect::<Vec<f64>>();
+                if row.iter().any(|value| !value.is_finite()) {
+                    return Err(format!("Non-finite value at node ({}, {}, {})", x, y, z));
+                }
+                rows.push(row);
+            }
+        }
+    }
+    Ok(rows)
+}
+
+/// Evaluates one expression per column and returns the CSV content of the
+/// resulting field.
+pub(crate) fn get_expression_field_content(
+    expressions: &[&str],
+    grid: [usize; 3],
+    coordinates: CoordinatesGui,
+    delta_x: f64,
+) -> Result<String, String> {
+    let rows = evaluate_expressions_on_grid(expressions, grid, coordinates, delta_x)?;
+    let mut content = String::new();
+    for row in rows {
+        let row = row.iter().map(f64::to_string).collect::<Vec<String>>();
+        content.push_str(&row.join(","));
+        content.push('\n');
+    }
+    Ok(content)
+}
+
+#[cfg(feature = "gui")]
+pub(crate) fn ui_coordinates(ui: &mut egui::Ui, coordinates: &mut CoordinatesGui) {
+    ui.label("Coordinates:");
+    ui.selectable_value(coordinates, CoordinatesGui::Lattice, "Lattice");
+    ui.selectable_value(coordinates, CoordinatesGui::Physical, "Physical");
+}
+
+#[cfg(feature = "gui")]
+pub(crate) fn ui_face_patch(
+    ui: &mut egui::Ui,
+    id_salt: String,
+    patch: &mut FacePatchGui,
+    grid: [usize; 3],
+    dim: Dimensionality,
+) {
+    let previous_face = patch.boundary_face;
+    egui::ComboBox::from_id_salt(id_salt)
+        .selected_text(patch.boundary_face.to_literal())
+        .show_ui(ui, |ui| {
+            for possible_face in BoundaryFaceGui::get_faces(dim) {
+                ui.selectable_value(
+                    &mut patch.boundary_face,
+                    possible_face,
+                    possible_face.to_literal(),
+                );
+            }
+        });
+    if patch.boundary_face != previous_face {
+        *patch = FacePatchGui::new(patch.boundary_face, grid);
+    }
+    let axes = get_tangential_axes(patch.boundary_face);
+    for (axis, [start, end]) in axes.iter().zip(&mut patch.ranges) {
+        if *axis >= dim.get_n_axes() {
+            continue;
+        }
+        ui.label(format!("{}:", AXIS_NAMES[*axis]));
+        ui.add(egui::DragValue::new(start).range(0..=grid[*axis].saturating_sub(1)));
+        ui.label("..");
+        ui.add(egui::DragValue::new(end).range(*start + 1..=grid[*axis]));
+    }
+}
+
+#[cfg(feature = "gui")]
+pub(crate) fn ui_expression(ui: &mut egui::Ui, label: &str, expression: &mut String) {
+    ui.horizontal(|ui| {
+        ui.label(label);
+        ui.text_edit_singleline(expression);
+        if let Err(e) = Expression::parse(expression, &FIELD_EXPRESSION_VARIABLES) {
+            ui.colored_label(ui.visuals().error_fg_color, e);
+        }
+    });
+}
+
+#[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub enum NodeTypeMaskGui {
     FromMapFile,
     OnlyFluidNodes,
+    Expression {
+        solid: String,
+        coordinates: CoordinatesGui,
+    },
 }
 
-#[derive(PartialEq, Eq)]
-pub(crate) enum ParModeGui {
+#[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub enum ParModeGui {
     Safe,
     Unsafe,
 }
 
-pub(crate) struct CargoGuiConfig {
-    pub(crate) case_name: String,
-    pub(crate) source_code_path: String,
-    pub(crate) par_mode: ParModeGui,
+#[cfg(feature = "gui")]
+pub(crate) const LBFLOW_SOA_VERSION: &str = "0.1.0";
+pub(crate) const UNSAFE_FASTPATH_FEATURE: &str = "unsafe_fastpath";
+
+#[derive(PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum GitReferenceGui {
+    Rev,
+    Tag,
+}
+
+#[derive(Default, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub enum DependencySourceGui {
+    #[default]
+    Path,
+    Git {
+        url: String,
+        reference: GitReferenceGui,
+        value: String,
+    },
+    Registry {
+        version: String,
+    },
+}
+
+#[cfg(feature = "gui")]
+impl DependencySourceGui {
+    pub(crate) fn get_label(&self) -> String {
+        match self {
+            DependencySourceGui::Path => "Path".to_string(),
+            DependencySourceGui::Git { .. } => "Git".to_string(),
+            DependencySourceGui::Registry { .. } => "Registry".to_string(),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum LtoGui {
+    Off,
+    Thin,
+    Fat,
+}
+
+impl LtoGui {
+    pub(crate) fn get_label(&self) -> String {
+        match self {
+            LtoGui::Off => "Off".to_string(),
+            LtoGui::Thin => "Thin".to_string(),
+            LtoGui::Fat => "Fat".to_string(),
+        }
+    }
+
+    fn to_literal(self) -> String {
+        match self {
+            LtoGui::Off => "false".to_string(),
+            LtoGui::Thin => "\"thin\"".to_string(),
+            LtoGui::Fat => "\"fat\"".to_string(),
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct ReleaseProfileGui {
+    pub lto: LtoGui,
+    pub codegen_units: Option<u32>,
+    pub panic_abort: bool,
+    pub debug: bool,
+}
+
+impl Default for ReleaseProfileGui {
+    fn default() -> Self {
+        ReleaseProfileGui {
+            lto: LtoGui::Off,
+            codegen_units: None,
+            panic_abort: false,
+            debug: false,
+        }
+    }
+}
+
+impl ReleaseProfileGui {
+    fn get_content(&self) -> String {
+        let mut lines = vec![];
+        if self.lto != LtoGui::Off {
+            lines.push(format!("lto = {}", self.lto.to_literal()));
+        }
+        if let Some(codegen_units) = self.codegen_units {
+            lines.push(format!("codegen-units = {}", codegen_units));
+        }
+        if self.panic_abort {
+            lines.push("panic = \"abort\"".to_string());
+        }
+        if self.debug {
+            lines.push("debug = true".to_string());
+        }
+        if lines.is_empty() {
+            return String::new();
+        }
+        format!("\n[profile.release]\n{}\n", lines.join("\n"))
+    }
+}
+
+pub(crate) const CARGO_CONFIG_FILE_PATH: &str = ".cargo/config.toml";
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CargoGuiConfig {
+    pub case_name: String,
+    pub source_code_path: String,
+    pub par_mode: ParModeGui,
+    #[serde(default)]
+    pub dependency_source: DependencySourceGui,
+    #[serde(default)]
+    pub release_profile: ReleaseProfileGui,
+    #[serde(default)]
+    pub target_cpu_native: bool,
+    #[serde(default)]
+    pub workspace: bool,
 }
 
 impl Default for CargoGuiConfig {
@@ -170,19 +602,50 @@ impl Default for CargoGuiConfig {
             case_name: "case_000_00".to_string(),
             source_code_path: String::from("../../../lbflow_soa"),
             par_mode: ParModeGui::Safe,
+            dependency_source: DependencySourceGui::Path,
+            release_profile: ReleaseProfileGui::default(),
+            target_cpu_native: false,
+            workspace: false,
         }
     }
 }
 
 impl CargoGuiConfig {
-    fn get_source_code_path_literal(&self) -> String {
-        format!("\"{}\"", self.source_code_path)
+    /// The path of a path dependency carries no version: cargo takes the one
+    /// of the source tree, which the source code check compares with the
+    /// supported one.
+    fn get_dependency_source_literal(&self) -> String {
+        let get_string_literal = |value: &str| toml::Value::String(value.to_string()).to_string();
+        match &self.dependency_source {
+            DependencySourceGui::Path => {
+                format!("path = {}", get_string_literal(&self.source_code_path))
+            }
+            DependencySourceGui::Git {
+                url,
+                reference,
+                value,
+            } => {
+                let reference_literal = match reference {
+                    GitReferenceGui::Rev => "rev",
+                    GitReferenceGui::Tag => "tag",
+                };
+                format!(
+                    "git = {}, {} = {}",
+                    get_string_literal(url),
+                    reference_literal,
+                    get_string_literal(value)
+                )
+            }
+            DependencySourceGui::Registry { version } => {
+                format!("version = {}", get_string_literal(version))
+            }
+        }
     }
 
     fn get_par_mode_literal(&self) -> String {
         match self.par_mode {
             ParModeGui::Safe => String::new(),
-            ParModeGui::Unsafe => String::from("\"unsafe_fastpath\""),
+            ParModeGui::Unsafe => format!("\"{}\"", UNSAFE_FASTPATH_FEATURE),
         }
     }
 }
@@ -190,8 +653,15 @@ impl CargoGuiConfig {
 impl CargoGuiConfig {
     pub fn get_cargo_toml(&self) -> String {
         let case_name = &self.case_name;
-        let source_code_path_literal = self.get_source_code_path_literal();
+        let dependency_source_literal = self.get_dependency_source_literal();
         let par_mode_literal = self.get_par_mode_literal();
+        // Profiles of workspace members are ignored by cargo, the workspace
+        // Cargo.toml carries them instead.
+        let release_profile_content = if self.workspace {
+            String::new()
+        } else {
+            self.release_profile.get_content()
+        };
         format!(
             r#"[package]
 name = "{case_name}"
@@ -199,8 +669,36 @@ version = "0.1.0"
 edition = "2024"
 
 [dependencies]
-lbflow_soa = {{ version = "0.1.0", path = {source_code_path_literal}, features = [{par_mode_literal}] }}
-"#
+lbflow_soa = {{ {dependency_source_literal}, features = [{par_mode_literal}] }}
+{release_profile_content}"#
+        )
+    }
+
+    #[cfg(feature = "gui")]
+    pub(crate) fn get_workspace_cargo_toml(&self, members: &[String]) -> String {
+        let members_literal = members
+            .iter()
+            .map(|member| format!("    {},", toml::Value::String(member.clone())))
+            .collect::<Vec<String>>()
+            .join("\n");
+        let release_profile_content = self.release_profile.get_content();
+        format!(
+            r#"[workspace]
+resolver = "3"
+members = [
+{members_literal}
+]
+{release_profile_content}"#
         )
     }
+
+    pub(crate) fn get_cargo_config_file(&self) -> Option<(String, String)> {
+        if !self.target_cpu_native {
+            return None;
+        }
+        let content = r#"[build]
+rustflags = ["-C", "target-cpu=native"]
+"#;
+        Some((CARGO_CONFIG_FILE_PATH.to_string(), content.to_string()))
+    }
 }
@@ -1,9 +1,14 @@
-use super::{Dimensionality, NodeTypeMaskGui};
+use super::{Dimensionality, FacePatchGui, NodeTypeMaskGui};
+use crate::geometry::NodeTypeMap;
+use serde::{Deserialize, Serialize};
 
+pub(crate) const MAP_FILE_PATH: &str = "./pre_processing/map.csv";
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct GuiConfig {
-    pub(crate) dim: Dimensionality,
-    pub(crate) grid: [usize; 3],
-    pub(crate) node_type_mask: NodeTypeMaskGui,
+    pub dim: Dimensionality,
+    pub grid: [usize; 3],
+    pub node_type_mask: NodeTypeMaskGui,
 }
 
 impl Default for GuiConfig {
@@ -27,18 +32,53 @@ impl GuiConfig {
         }
     }
 
-    fn get_node_type_mask_literal(&self) -> String {
+    fn get_node_type_mask_literal(&self, is_patched: bool) -> String {
         match self.node_type_mask {
-            NodeTypeMaskGui::OnlyFluidNodes => "OnlyFluidNodes".to_string(),
-            NodeTypeMaskGui::FromMapFile => "FromMapFile".to_string(),
+            NodeTypeMaskGui::OnlyFluidNodes if !is_patched => "OnlyFluidNodes".to_string(),
+            _ => "FromMapFile".to_string(),
+        }
+    }
+
+    pub(crate) fn get_node_type_map(&self, delta_x: f64) -> Option<Result<NodeTypeMap, String>> {
+        match &self.node_type_mask {
+            NodeTypeMaskGui::OnlyFluidNodes => Some(Ok(NodeTypeMap::new_fluid(self.grid))),
+            NodeTypeMaskGui::FromMapFile => None,
+            NodeTypeMaskGui::Expression { solid, coordinates } => Some(
+                NodeTypeMap::from_expression(solid, self.grid, *coordinates, delta_x),
+            ),
+        }
+    }
+
+    /// Returns the map file to generate, if any. With boundary patches the
+    /// map is always generated, their faces being closed outside of them.
+    pub(crate) fn get_map_file(
+        &self,
+        delta_x: f64,
+        patches: &[&FacePatchGui],
+    ) -> Result<Option<(String, String)>, String> {
+        if patches.is_empty() {
+            if let NodeTypeMaskGui::Expression { solid, coordinates } = &self.node_type_mask {
+                let map = NodeTypeMap::from_expression(solid, self.grid, *coordinates, delta_x)?;
+                return Ok(Some((MAP_FILE_PATH.to_string(), map.get_file_content())));
+            }
+            return Ok(None);
         }
+        let Some(map) = self.get_node_type_map(delta_x) else {
+            return Err(
+                "Boundary patches need a generated node map, not one read from a map file"
+                    .to_string(),
+            );
+        };
+        let mut map = map?;
+        map.close_faces_outside_patches(patches, self.dim);
+        Ok(Some((MAP_FILE_PATH.to_string(), map.get_file_content())))
     }
 }
 
 impl GuiConfig {
-    pub(crate) fn get_domain_content(&self) -> String {
+    pub(crate) fn get_domain_content(&self, is_patched: bool) -> String {
         let grid_literal = self.get_grid_literal();
-        let node_type_mask_literal = self.get_node_type_mask_literal();
+        let node_type_mask_literal = self.get_node_type_mask_literal(is_patched);
         format!(
             r#"    let domain = DomainParams {{
         grid: {grid_literal},
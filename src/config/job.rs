@@ -0,0 +1,165 @@
+#[cfg(feature = "gui")]
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+#[derive(PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum SchedulerGui {
+    Slurm,
+    Pbs,
+}
+
+impl SchedulerGui {
+    #[cfg(feature = "gui")]
+    pub(crate) fn get_label(&self) -> String {
+        match self {
+            SchedulerGui::Slurm => "SLURM".to_string(),
+            SchedulerGui::Pbs => "PBS".to_string(),
+        }
+    }
+
+    fn get_file_name(&self) -> String {
+        match self {
+            SchedulerGui::Slurm => "job.slurm".to_string(),
+            SchedulerGui::Pbs => "job.pbs".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GuiConfig {
+    pub enabled: bool,
+    pub scheduler: SchedulerGui,
+    pub partition: String,
+    pub walltime: String,
+    pub cores: usize,
+    pub memory_gb: usize,
+    pub modules: String,
+}
+
+impl Default for GuiConfig {
+    fn default() -> Self {
+        GuiConfig {
+            enabled: false,
+            scheduler: SchedulerGui::Slurm,
+            partition: String::new(),
+            walltime: "24:00:00".to_string(),
+            cores: 16,
+            memory_gb: 16,
+            modules: String::new(),
+        }
+    }
+}
+
+impl GuiConfig {
+    fn get_directives_content(&self, case_name: &str) -> String {
+        let mut directives = vec![];
+        match self.scheduler {
+            SchedulerGui::Slurm => {
+                directives.push(format!("#SBATCH --job-name={}", case_name));
+                if !self.partition.trim().is_empty() {
+                    directives.push(format!("#SBATCH --partition={}", self.partition.trim()));
+                }
+                directives.push(format!("#SBATCH --time={}", self.walltime.trim()));
+                directives.push("#SBATCH --nodes=1".to_string());
+                directives.push("#SBATCH --ntasks=1".to_string());
+                directives.push(format!("#SBATCH --cpus-per-task={}", self.cores));
+                directives.push(format!("#SBATCH --mem={}G", self.memory_gb));
+                directives.push(format!("#SBATCH --output={}_%j.out", case_name));
+            }
+            SchedulerGui::Pbs => {
+                directives.push(format!("#PBS -N {}", case_name));
+                if !self.partition.trim().is_empty() {
+                    directives.push(format!("#PBS -q {}", self.partition.trim()));
+                }
+                directives.push(format!("#PBS -l walltime={}", self.walltime.trim()));
+                directives.push(format!(
+                    "#PBS -l select=1:ncpus={}:mem={}gb",
+                    self.cores, self.memory_gb
+                ));
+                directives.push("#PBS -j oe".to_string());
+            }
+        }
+        directives.join("\n")
+    }
+
+    fn get_change_dir_content(&self) -> String {
+        match self.scheduler {
+            SchedulerGui::Slurm => "cd \"$SLURM_SUBMIT_DIR\"".to_string(),
+            SchedulerGui::Pbs => "cd \"$PBS_O_WORKDIR\"".to_string(),
+        }
+    }
+
+    fn get_modules_content(&self) -> String {
+        let module_loads = self
+            .modules
+            .lines()
+            .map(|module| module.trim())
+            .filter(|module| !module.is_empty())
+            .map(|module| format!("module load {}", module))
+            .collect::<Vec<String>>();
+        if module_loads.is_empty() {
+            return String::new();
+        }
+        format!("\n{}\n", module_loads.join("\n"))
+    }
+
+    pub(crate) fn get_job_script_file(&self, case_name: &str) -> Option<(String, String)> {
+        if !self.enabled {
+            return None;
+        }
+        let directives_content = self.get_directives_content(case_name);
+        let change_dir_content = self.get_change_dir_content();
+        let modules_content = self.get_modules_content();
+        let cores = self.cores;
+        let content = format!(
+            r#"#!/bin/bash
+{directives_content}
+
+set -e
+{change_dir_content}
+{modules_content}
+export RAYON_NUM_THREADS={cores}
+
+cargo build --release
+cargo run --release
+"#
+        );
+        Some((self.scheduler.get_file_name(), content))
+    }
+}
+
+#[cfg(feature = "gui")]
+impl GuiConfig {
+    pub(crate) fn ui_job_script(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(&mut self.enabled, "Write a job script");
+        if !self.enabled {
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.label("Scheduler:");
+            for scheduler in [SchedulerGui::Slurm, SchedulerGui::Pbs] {
+                ui.selectable_value(&mut self.scheduler, scheduler, scheduler.get_label());
+            }
+        });
+        ui.horizontal(|ui| {
+            let label = match self.scheduler {
+                SchedulerGui::Slurm => "Partition:",
+                SchedulerGui::Pbs => "Queue:",
+            };
+            ui.label(label);
+            ui.text_edit_singleline(&mut self.partition);
+        });
+        ui.horizontal(|ui| {
+            ui.label("Walltime (hh:mm:ss):");
+            ui.text_edit_singleline(&mut self.walltime);
+        });
+        ui.horizontal(|ui| {
+            ui.label("Cores:");
+            ui.add(egui::DragValue::new(&mut self.cores).range(1..=1024));
+            ui.label("Memory (GB):");
+            ui.add(egui::DragValue::new(&mut self.memory_gb).range(1..=4096));
+        });
+        ui.label("Modules to load (one per line):");
+        ui.text_edit_multiline(&mut self.modules);
+    }
+}
@@ -1,8 +1,14 @@
+#[cfg(feature = "gui")]
 use super::LatticeGuiConfig;
-use super::{BoundaryFaceGui, CollisionOperatorGui, Dimensionality, VelocitySetGui};
+use super::{BoundaryFaceGui, CollisionOperatorGui, Dimensionality, FacePatchGui, VelocitySetGui};
+use super::{CoordinatesGui, get_expression_field_content};
+use serde::{Deserialize, Serialize};
 
-#[derive(PartialEq)]
-pub(crate) enum BoundaryConditionGui {
+pub(crate) const INITIAL_DENSITY_FILE_PATH: &str = "./pre_processing/density.csv";
+pub(crate) const INITIAL_VELOCITY_FILE_PATH: &str = "./pre_processing/velocity.csv";
+
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+pub enum BoundaryConditionGui {
     NoSlip,
     BounceBack { rho: f64, ux: f64, uy: f64, uz: f64 },
     AntiBounceBack { rho: f64 },
@@ -10,6 +16,15 @@ pub(crate) enum BoundaryConditionGui {
 }
 
 impl BoundaryConditionGui {
+    pub(crate) fn get_label(&self) -> &'static str {
+        match self {
+            BoundaryConditionGui::NoSlip => "No-slip",
+            BoundaryConditionGui::BounceBack { .. } => "Bounce-back",
+            BoundaryConditionGui::AntiBounceBack { .. } => "Anti-bounce-back",
+            BoundaryConditionGui::Periodic => "Periodic",
+        }
+    }
+
     fn to_literal(&self, dim: &Dimensionality) -> String {
         match self {
             BoundaryConditionGui::NoSlip => "MomentumBC::NoSlip".to_string(),
@@ -31,35 +46,69 @@ impl BoundaryConditionGui {
     }
 }
 
-pub(crate) struct FaceBC {
-    pub(crate) boundary_face: BoundaryFaceGui,
-    pub(crate) boundary_condition: BoundaryConditionGui,
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FaceBC {
+    pub boundary_face: BoundaryFaceGui,
+    pub boundary_condition: BoundaryConditionGui,
 }
 
-#[derive(PartialEq)]
-pub(crate) enum InitialDensityGui {
-    Uniform { rho: f64 },
-    FromTimeStep { time_step: usize },
-    FromFile { file_path: String },
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PatchBC {
+    pub patch: FacePatchGui,
+    pub boundary_condition: BoundaryConditionGui,
 }
 
-#[derive(PartialEq)]
-pub(crate) enum InitialVelocityGui {
-    Uniform { ux: f64, uy: f64, uz: f64 },
-    FromTimeStep { time_step: usize },
-    FromFile { file_path: String },
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+pub enum InitialDensityGui {
+    Uniform {
+        rho: f64,
+    },
+    FromTimeStep {
+        time_step: usize,
+    },
+    FromFile {
+        file_path: String,
+    },
+    Expression {
+        rho: String,
+        coordinates: CoordinatesGui,
+    },
 }
 
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+pub enum InitialVelocityGui {
+    Uniform {
+        ux: f64,
+        uy: f64,
+        uz: f64,
+    },
+    FromTimeStep {
+        time_step: usize,
+    },
+    FromFile {
+        file_path: String,
+    },
+    Expression {
+        ux: String,
+        uy: String,
+        uz: String,
+        coordinates: CoordinatesGui,
+    },
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct GuiConfig {
-    pub(crate) velocity_set: VelocitySetGui,
-    pub(crate) collision_operator: CollisionOperatorGui,
-    pub(crate) delta_x: f64,
-    pub(crate) delta_t: f64,
-    pub(crate) physical_density: f64,
-    pub(crate) reference_pressure: f64,
-    pub(crate) initial_density: InitialDensityGui,
-    pub(crate) initial_velocity: InitialVelocityGui,
-    pub(crate) boundary_conditions: Vec<FaceBC>,
+    pub velocity_set: VelocitySetGui,
+    pub collision_operator: CollisionOperatorGui,
+    pub delta_x: f64,
+    pub delta_t: f64,
+    pub physical_density: f64,
+    pub reference_pressure: f64,
+    pub initial_density: InitialDensityGui,
+    pub initial_velocity: InitialVelocityGui,
+    pub boundary_conditions: Vec<FaceBC>,
+    #[serde(default)]
+    pub boundary_patches: Vec<PatchBC>,
 }
 
 impl Default for GuiConfig {
@@ -95,11 +144,88 @@ impl Default for GuiConfig {
                     boundary_condition: BoundaryConditionGui::NoSlip,
                 },
             ],
+            boundary_patches: vec![],
         }
     }
 }
 
 impl GuiConfig {
+    pub(crate) fn get_face_bc(&self, boundary_face: BoundaryFaceGui) -> Option<&FaceBC> {
+        self.boundary_conditions
+            .iter()
+            .find(|face_bc| face_bc.boundary_face == boundary_face)
+    }
+
+    #[cfg(feature = "gui")]
+    /// Sets the condition of the given faces, adding the faces that have none.
+    pub(crate) fn set_boundary_condition(
+        &mut self,
+        boundary_faces: &[BoundaryFaceGui],
+        boundary_condition: &BoundaryConditionGui,
+    ) {
+        for boundary_face in boundary_faces {
+            match self
+                .boundary_conditions
+                .iter_mut()
+                .find(|face_bc| face_bc.boundary_face == *boundary_face)
+            {
+                Some(face_bc) => face_bc.boundary_condition = boundary_condition.clone(),
+                None => self.boundary_conditions.push(FaceBC {
+                    boundary_face: *boundary_face,
+                    boundary_condition: boundary_condition.clone(),
+                }),
+            }
+        }
+    }
+
+    /// Face conditions as they are generated: a face with patches takes the
+    /// condition of its patches, the rest of it being turned into solid nodes.
+    pub(crate) fn get_generated_boundary_conditions(&self) -> Vec<FaceBC> {
+        self.boundary_conditions
+            .iter()
+            .map(|face_bc| FaceBC {
+                boundary_face: face_bc.boundary_face,
+                boundary_condition: self
+                    .boundary_patches
+                    .iter()
+                    .find(|patch_bc| patch_bc.patch.boundary_face == face_bc.boundary_face)
+                    .map_or(&face_bc.boundary_condition, |patch_bc| {
+                        &patch_bc.boundary_condition
+                    })
+                    .clone(),
+            })
+            .collect()
+    }
+
+    /// Checks that the patches can be generated: lbflow_soa takes one
+    /// condition per face, so all the patches of a face must share it.
+    pub(crate) fn validate_boundary_patches(&self) -> Result<(), String> {
+        for (i, patch_bc) in self.boundary_patches.iter().enumerate() {
+            let boundary_face = patch_bc.patch.boundary_face;
+            if self.get_face_bc(boundary_face).is_none() {
+                return Err(format!(
+                    "momentum: patch {} is on the {} face, which has no condition",
+                    i,
+                    boundary_face.to_literal()
+                ));
+            }
+            if patch_bc.boundary_condition == BoundaryConditionGui::Periodic {
+                return Err(format!("momentum: patch {} cannot be periodic", i));
+            }
+            let is_conflicting = self.boundary_patches[..i].iter().any(|other| {
+                other.patch.boundary_face == boundary_face
+                    && other.boundary_condition != patch_bc.boundary_condition
+            });
+            if is_conflicting {
+                return Err(format!(
+                    "momentum: the patches of the {} face have different conditions, but lbflow_soa takes one condition per face",
+                    boundary_face.to_literal()
+                ));
+            }
+        }
+        Ok(())
+    }
+
     fn get_velocity_set_literal(&self) -> String {
         match self.velocity_set {
             VelocitySetGui::D2Q5 => "D2Q5".to_string(),
@@ -132,6 +258,12 @@ impl GuiConfig {
             InitialDensityGui::FromFile { file_path } => {
                 format!("InitialDensity::FromFile(\"{}\")", file_path)
             }
+            InitialDensityGui::Expression { .. } => {
+                format!(
+                    "InitialDensity::FromFile(\"{}\")",
+                    INITIAL_DENSITY_FILE_PATH
+                )
+            }
         }
     }
 
@@ -154,12 +286,18 @@ impl GuiConfig {
             InitialVelocityGui::FromFile { file_path } => {
                 format!("InitialVelocity::FromFile(\"{}\")", file_path)
             }
+            InitialVelocityGui::Expression { .. } => {
+                format!(
+                    "InitialVelocity::FromFile(\"{}\")",
+                    INITIAL_VELOCITY_FILE_PATH
+                )
+            }
         }
     }
 
     fn get_boundary_conditions_literal(&self, dim: &Dimensionality) -> String {
         let mut boundary_conditions_literals = vec![];
-        for face_bc in &self.boundary_conditions {
+        for face_bc in &self.get_generated_boundary_conditions() {
             let boundary_face_literal = face_bc.boundary_face.to_literal();
             let boundary_condition_literal = face_bc.boundary_condition.to_literal(dim);
             boundary_conditions_literals.push(format!(
@@ -171,6 +309,39 @@ impl GuiConfig {
     }
 }
 
+impl GuiConfig {
+    /// Returns the path (relative to the case directory) and content of every
+    /// initial field file that has to be generated from an expression.
+    pub(crate) fn get_initial_field_files(
+        &self,
+        grid: [usize; 3],
+        dim: &Dimensionality,
+    ) -> Result<Vec<(String, String)>, String> {
+        let mut files = vec![];
+        if let InitialDensityGui::Expression { rho, coordinates } = &self.initial_density {
+            let content = get_expression_field_content(&[rho], grid, *coordinates, self.delta_x)?;
+            files.push((INITIAL_DENSITY_FILE_PATH.to_string(), content));
+        }
+        if let InitialVelocityGui::Expression {
+            ux,
+            uy,
+            uz,
+            coordinates,
+        } = &self.initial_velocity
+        {
+            let expressions = match dim {
+                Dimensionality::D2 => vec![ux.as_str(), uy.as_str()],
+                Dimensionality::D3 => vec![ux.as_str(), uy.as_str(), uz.as_str()],
+            };
+            let content =
+                get_expression_field_content(&expressions, grid, *coordinates, self.delta_x)?;
+            files.push((INITIAL_VELOCITY_FILE_PATH.to_string(), content));
+        }
+        Ok(files)
+    }
+}
+
+#[cfg(feature = "gui")]
 impl LatticeGuiConfig for GuiConfig {
     fn get_velocity_set_gui(&mut self) -> &mut VelocitySetGui {
         &mut self.velocity_set
@@ -1,9 +1,19 @@
+#[cfg(feature = "gui")]
+use super::Dimensionality;
+#[cfg(feature = "gui")]
 use super::LatticeGuiConfig;
-use super::{BoundaryFaceGui, CollisionOperatorGui, Dimensionality, VelocitySetGui};
+#[cfg(feature = "gui")]
+use super::momentum;
+use super::{BoundaryFaceGui, CollisionOperatorGui, FacePatchGui, VelocitySetGui};
+use super::{CoordinatesGui, get_expression_field_content};
+#[cfg(feature = "gui")]
+use super::{PATCH_LIMITATION_NOTE, ui_coordinates, ui_expression, ui_face_patch};
+#[cfg(feature = "gui")]
 use eframe::egui;
+use serde::{Deserialize, Serialize};
 
-#[derive(PartialEq)]
-pub(crate) enum BoundaryConditionGui {
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+pub enum BoundaryConditionGui {
     AntiBounceBack { value: f64 },
     AntiBBNoFlux,
     BBNoFlux,
@@ -13,6 +23,27 @@ pub(crate) enum BoundaryConditionGui {
 }
 
 impl BoundaryConditionGui {
+    pub(crate) fn is_no_flux(&self) -> bool {
+        matches!(
+            self,
+            BoundaryConditionGui::AntiBBNoFlux
+                | BoundaryConditionGui::BBNoFlux
+                | BoundaryConditionGui::ZerothOrderNoFlux
+                | BoundaryConditionGui::SecondOrderNoFlux
+        )
+    }
+
+    pub(crate) fn get_label(&self) -> &'static str {
+        match self {
+            BoundaryConditionGui::AntiBounceBack { .. } => "Anti-bounce-back",
+            BoundaryConditionGui::AntiBBNoFlux => "No-flux (ABB)",
+            BoundaryConditionGui::BBNoFlux => "No-flux (BB)",
+            BoundaryConditionGui::ZerothOrderNoFlux => "No-flux (Zeroth order)",
+            BoundaryConditionGui::SecondOrderNoFlux => "No-flux (Second order)",
+            BoundaryConditionGui::Periodic => "Periodic",
+        }
+    }
+
     fn to_literal(&self) -> String {
         match self {
             BoundaryConditionGui::AntiBounceBack { value } => {
@@ -31,31 +62,51 @@ impl BoundaryConditionGui {
     }
 }
 
-#[derive(PartialEq)]
-pub(crate) enum InnerBoundaryConditionGui {
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+pub enum InnerBoundaryConditionGui {
     InnerAntiBounceBack,
     InnerBounceBack,
 }
 
-pub(crate) struct FaceBC {
-    pub(crate) boundary_face: BoundaryFaceGui,
-    pub(crate) boundary_condition: BoundaryConditionGui,
+#[derive(Clone, Serialize, Deserialize)]
+pub struct FaceBC {
+    pub boundary_face: BoundaryFaceGui,
+    pub boundary_condition: BoundaryConditionGui,
 }
 
-#[derive(PartialEq)]
-pub(crate) enum InitialScalarValueGui {
-    Uniform { value: f64 },
-    FromTimeStep { time_step: usize },
-    FromFile { file_path: String },
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PatchBC {
+    pub patch: FacePatchGui,
+    pub boundary_condition: BoundaryConditionGui,
 }
 
+#[derive(PartialEq, Clone, Serialize, Deserialize)]
+pub enum InitialScalarValueGui {
+    Uniform {
+        value: f64,
+    },
+    FromTimeStep {
+        time_step: usize,
+    },
+    FromFile {
+        file_path: String,
+    },
+    Expression {
+        value: String,
+        coordinates: CoordinatesGui,
+    },
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct GuiConfig {
-    pub(crate) name: String,
-    pub(crate) collision_operator: CollisionOperatorGui,
-    pub(crate) velocity_set: VelocitySetGui,
-    pub(crate) initial_scalar_value: InitialScalarValueGui,
-    pub(crate) boundary_conditions: Vec<FaceBC>,
-    pub(crate) inner_boundary_condition: InnerBoundaryConditionGui,
+    pub name: String,
+    pub collision_operator: CollisionOperatorGui,
+    pub velocity_set: VelocitySetGui,
+    pub initial_scalar_value: InitialScalarValueGui,
+    pub boundary_conditions: Vec<FaceBC>,
+    #[serde(default)]
+    pub boundary_patches: Vec<PatchBC>,
+    pub inner_boundary_condition: InnerBoundaryConditionGui,
 }
 
 impl Default for GuiConfig {
@@ -83,16 +134,170 @@ impl Default for GuiConfig {
                     boundary_condition: BoundaryConditionGui::AntiBBNoFlux,
                 },
             ],
+            boundary_patches: vec![],
             inner_boundary_condition: InnerBoundaryConditionGui::InnerBounceBack,
         }
     }
 }
 
+#[cfg(feature = "gui")]
+impl BoundaryConditionGui {
+    /// Scalar counterpart of a momentum boundary condition: walls let no
+    /// scalar through and outlets, whether velocity or pressure ones, let it
+    /// leave with a zero gradient. Pressure faces at `outlet_rho`, the lowest
+    /// pressure of the domain, are outlets. Returns `None` on inflow faces,
+    /// where only the user knows the scalar value that enters.
+    fn from_momentum(
+        boundary_face: BoundaryFaceGui,
+        m_boundary_condition: &momentum::BoundaryConditionGui,
+        outlet_rho: f64,
+    ) -> Option<Self> {
+        match m_boundary_condition {
+            momentum::BoundaryConditionGui::NoSlip => Some(BoundaryConditionGui::AntiBBNoFlux),
+            momentum::BoundaryConditionGui::BounceBack { ux, uy, uz, .. } => {
+                let normal_velocity = [*ux, *uy, *uz][boundary_face.get_axis()];
+                let inward_velocity = if boundary_face.is_min_side() {
+                    normal_velocity
+                } else {
+                    -normal_velocity
+                };
+                if inward_velocity < 0.0 {
+                    Some(BoundaryConditionGui::ZerothOrderNoFlux)
+                } else if inward_velocity == 0.0 {
+                    Some(BoundaryConditionGui::AntiBBNoFlux)
+                } else {
+                    None
+                }
+            }
+            momentum::BoundaryConditionGui::AntiBounceBack { rho } => {
+                (*rho <= outlet_rho).then_some(BoundaryConditionGui::ZerothOrderNoFlux)
+            }
+            momentum::BoundaryConditionGui::Periodic => Some(BoundaryConditionGui::Periodic),
+        }
+    }
+}
+
+impl GuiConfig {
+    #[cfg(feature = "gui")]
+    /// Sets the condition of the given faces, adding the faces that have none.
+    pub(crate) fn set_boundary_condition(
+        &mut self,
+        boundary_faces: &[BoundaryFaceGui],
+        boundary_condition: &BoundaryConditionGui,
+    ) {
+        for boundary_face in boundary_faces {
+            match self
+                .boundary_conditions
+                .iter_mut()
+                .find(|face_bc| face_bc.boundary_face == *boundary_face)
+            {
+                Some(face_bc) => face_bc.boundary_condition = boundary_condition.clone(),
+                None => self.boundary_conditions.push(FaceBC {
+                    boundary_face: *boundary_face,
+                    boundary_condition: boundary_condition.clone(),
+                }),
+            }
+        }
+    }
+
+    /// Face conditions as they are generated: a face with patches takes the
+    /// condition of its patches, the rest of it being turned into solid nodes.
+    pub(crate) fn get_generated_boundary_conditions(&self) -> Vec<FaceBC> {
+        self.boundary_conditions
+            .iter()
+            .map(|face_bc| FaceBC {
+                boundary_face: face_bc.boundary_face,
+                boundary_condition: self
+                    .boundary_patches
+                    .iter()
+                    .find(|patch_bc| patch_bc.patch.boundary_face == face_bc.boundary_face)
+                    .map_or(&face_bc.boundary_condition, |patch_bc| {
+                        &patch_bc.boundary_condition
+                    })
+                    .clone(),
+            })
+            .collect()
+    }
+
+    /// Checks that the patches can be generated: lbflow_soa takes one
+    /// condition per face, so all the patches of a face must share it.
+    pub(crate) fn validate_boundary_patches(&self) -> Result<(), String> {
+        for (i, patch_bc) in self.boundary_patches.iter().enumerate() {
+            let boundary_face = patch_bc.patch.boundary_face;
+            if self.get_face_bc(boundary_face).is_none() {
+                return Err(format!(
+                    "{}: patch {} is on the {} face, which has no condition",
+                    self.name,
+                    i,
+                    boundary_face.to_literal()
+                ));
+            }
+            if patch_bc.boundary_condition == BoundaryConditionGui::Periodic {
+                return Err(format!("{}: patch {} cannot be periodic", self.name, i));
+            }
+            let is_conflicting = self.boundary_patches[..i].iter().any(|other| {
+                other.patch.boundary_face == boundary_face
+                    && other.boundary_condition != patch_bc.boundary_condition
+            });
+            if is_conflicting {
+                return Err(format!(
+                    "{}: the patches of the {} face have different conditions, but lbflow_soa takes one condition per face",
+                    self.name,
+                    boundary_face.to_literal()
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets the scalar conditions matching the momentum ones and returns the
+    /// inflow faces, which keep their condition.
+    #[cfg(feature = "gui")]
+    pub(crate) fn map_momentum_boundary_conditions(
+        &mut self,
+        m_boundary_conditions: &[momentum::FaceBC],
+    ) -> Vec<BoundaryFaceGui> {
+        let outlet_rho = m_boundary_conditions
+            .iter()
+            .filter_map(|m_face_bc| match m_face_bc.boundary_condition {
+                momentum::BoundaryConditionGui::AntiBounceBack { rho } => Some(rho),
+                _ => None,
+            })
+            .fold(f64::INFINITY, f64::min);
+        let mut inflow_faces = vec![];
+        for m_face_bc in m_boundary_conditions {
+            match BoundaryConditionGui::from_momentum(
+                m_face_bc.boundary_face,
+                &m_face_bc.boundary_condition,
+                outlet_rho,
+            ) {
+                Some(boundary_condition) => {
+                    self.set_boundary_condition(&[m_face_bc.boundary_face], &boundary_condition)
+                }
+                None => inflow_faces.push(m_face_bc.boundary_face),
+            }
+        }
+        inflow_faces
+    }
+}
+
 impl GuiConfig {
+    pub(crate) fn get_face_bc(&self, boundary_face: BoundaryFaceGui) -> Option<&FaceBC> {
+        self.boundary_conditions
+            .iter()
+            .find(|face_bc| face_bc.boundary_face == boundary_face)
+    }
+
     pub(crate) fn get_s_params_name_literal(&self) -> String {
         format!("s_params_{}", self.name)
     }
 
+    /// The `scalar_` prefix keeps the file apart from the momentum and map
+    /// files of `pre_processing`, whatever the scalar name.
+    fn get_initial_field_file_path(&self) -> String {
+        format!("./pre_processing/scalar_{}.csv", self.name)
+    }
+
     fn get_name_literal(&self) -> String {
         format!("\"{}\"", self.name)
     }
@@ -129,12 +334,32 @@ impl GuiConfig {
             InitialScalarValueGui::FromFile { file_path } => {
                 format!("InitialScalarValue::FromFile(\"{}\")", file_path)
             }
+            InitialScalarValueGui::Expression { .. } => {
+                format!(
+                    "InitialScalarValue::FromFile(\"{}\")",
+                    self.get_initial_field_file_path()
+                )
+            }
+        }
+    }
+
+    pub(crate) fn get_initial_field_files(
+        &self,
+        grid: [usize; 3],
+        delta_x: f64,
+    ) -> Result<Vec<(String, String)>, String> {
+        match &self.initial_scalar_value {
+            InitialScalarValueGui::Expression { value, coordinates } => {
+                let content = get_expression_field_content(&[value], grid, *coordinates, delta_x)?;
+                Ok(vec![(self.get_initial_field_file_path(), content)])
+            }
+            _ => Ok(vec![]),
         }
     }
 
     fn get_boundary_conditions_literal(&self) -> String {
         let mut boundary_conditions_literals = vec![];
-        for face_bc in &self.boundary_conditions {
+        for face_bc in &self.get_generated_boundary_conditions() {
             let boundary_face_literal = face_bc.boundary_face.to_literal();
             let boundary_condition_literal = face_bc.boundary_condition.to_literal();
             boundary_conditions_literals.push(format!(
@@ -153,6 +378,7 @@ impl GuiConfig {
     }
 }
 
+#[cfg(feature = "gui")]
 impl LatticeGuiConfig for GuiConfig {
     fn get_velocity_set_gui(&mut self) -> &mut VelocitySetGui {
         &mut self.velocity_set
@@ -163,6 +389,59 @@ impl LatticeGuiConfig for GuiConfig {
     }
 }
 
+#[cfg(feature = "gui")]
+fn ui_boundary_condition(
+    ui: &mut egui::Ui,
+    id_salt: String,
+    boundary_condition: &mut BoundaryConditionGui,
+) {
+    let cur_abb_value = match boundary_condition {
+        BoundaryConditionGui::AntiBounceBack { value } => *value,
+        _ => 0.0,
+    };
+    egui::ComboBox::from_id_salt(id_salt)
+        .selected_text(boundary_condition.get_label())
+        .show_ui(ui, |ui| {
+            ui.selectable_value(
+                boundary_condition,
+                BoundaryConditionGui::AntiBounceBack {
+                    value: cur_abb_value,
+                },
+                "Anti-bounce-back",
+            );
+            ui.selectable_value(
+                boundary_condition,
+                BoundaryConditionGui::AntiBBNoFlux,
+                "No-flux (ABB)",
+            );
+            ui.selectable_value(
+                boundary_condition,
+                BoundaryConditionGui::BBNoFlux,
+                "No-flux (BB)",
+            );
+            ui.selectable_value(
+                boundary_condition,
+                BoundaryConditionGui::ZerothOrderNoFlux,
+                "No-flux (Zeroth order)",
+            );
+            ui.selectable_value(
+                boundary_condition,
+                BoundaryConditionGui::SecondOrderNoFlux,
+                "No-flux (Second order)",
+            );
+            ui.selectable_value(
+                boundary_condition,
+                BoundaryConditionGui::Periodic,
+                "Periodic",
+            );
+        });
+    if let BoundaryConditionGui::AntiBounceBack { value } = boundary_condition {
+        ui.label("value:");
+        ui.add(egui::DragValue::new(value).speed(0.01));
+    }
+}
+
+#[cfg(feature = "gui")]
 impl GuiConfig {
     pub(crate) fn ui_scalar_name(&mut self, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
@@ -192,7 +471,13 @@ impl GuiConfig {
             };
             let cur_file_path = match &self.initial_scalar_value {
                 InitialScalarValueGui::FromFile { file_path } => file_path.clone(),
-                _ => format!("./pre_processing/{}.csv", self.name),
+                _ => self.get_initial_field_file_path(),
+            };
+            let (cur_expression, cur_coordinates) = match &self.initial_scalar_value {
+                InitialScalarValueGui::Expression { value, coordinates } => {
+                    (value.clone(), *coordinates)
+                }
+                _ => (cur_value.to_string(), CoordinatesGui::Lattice),
             };
 
             egui::ComboBox::from_id_salt("initial_scalar_value_combo_box")
@@ -200,6 +485,7 @@ impl GuiConfig {
                     InitialScalarValueGui::Uniform { value: _ } => "Uniform",
                     InitialScalarValueGui::FromTimeStep { time_step: _ } => "From time step",
                     InitialScalarValueGui::FromFile { file_path: _ } => "From file",
+                    InitialScalarValueGui::Expression { .. } => "Expression",
                 })
                 .show_ui(ui, |ui| {
                     ui.selectable_value(
@@ -221,8 +507,17 @@ impl GuiConfig {
                         },
                         "From file",
                     );
+                    ui.selectable_value(
+                        &mut self.initial_scalar_value,
+                        InitialScalarValueGui::Expression {
+                            value: cur_expression.clone(),
+                            coordinates: cur_coordinates,
+                        },
+                        "Expression",
+                    );
                 });
         });
+
         ui.horizontal(|ui| match &mut self.initial_scalar_value {
             InitialScalarValueGui::Uniform { value } => {
                 ui.label("Value:");
@@ -240,34 +535,31 @@ impl GuiConfig {
                 ui.label("File path:");
                 ui.text_edit_singleline(file_path);
             }
+            InitialScalarValueGui::Expression { value, coordinates } => {
+                ui.vertical(|ui| {
+                    ui_expression(ui, "Value:", value);
+                    ui.horizontal(|ui| ui_coordinates(ui, coordinates));
+                });
+            }
         });
     }
 
-    pub(crate) fn ui_boundary_conditions(&mut self, ui: &mut egui::Ui, dim: Dimensionality) {
+    pub(crate) fn ui_boundary_conditions(
+        &mut self,
+        ui: &mut egui::Ui,
+        grid: [usize; 3],
+        dim: Dimensionality,
+    ) {
         ui.heading("Boundary conditions");
-        let (possible_faces, number_of_faces) = match dim {
-            Dimensionality::D2 => (
-                vec![
-                    BoundaryFaceGui::West,
-                    BoundaryFaceGui::East,
-                    BoundaryFaceGui::South,
-                    BoundaryFaceGui::North,
-                ],
-                4,
-            ),
-            Dimensionality::D3 => (
-                vec![
-                    BoundaryFaceGui::West,
-                    BoundaryFaceGui::East,
-                    BoundaryFaceGui::South,
-                    BoundaryFaceGui::North,
-                    BoundaryFaceGui::Bottom,
-                    BoundaryFaceGui::Top,
-                ],
-                6,
-            ),
-        };
-        for i in 0..number_of_faces {
+        let possible_faces = BoundaryFaceGui::get_faces(dim);
+        let bulk_id = ui.id().with("bulk_boundary_conditions");
+        let (mut bulk_boundary_condition, mut selected_faces) = ui.memory(|mem| {
+            mem.data
+                .get_temp::<(BoundaryConditionGui, Vec<bool>)>(bulk_id)
+                .unwrap_or((BoundaryConditionGui::AntiBBNoFlux, vec![]))
+        });
+        selected_faces.resize(possible_faces.len(), false);
+        for (i, is_selected) in selected_faces.iter_mut().enumerate() {
             if i >= self.boundary_conditions.len() {
                 self.boundary_conditions.push(FaceBC {
                     boundary_face: possible_faces[i],
@@ -276,6 +568,7 @@ impl GuiConfig {
             }
             let face_bc = &mut self.boundary_conditions[i];
             ui.horizontal(|ui| {
+                ui.checkbox(is_selected, "");
                 ui.label(format!("Face {}:", i));
                 egui::ComboBox::from_id_salt(format!("boundary_face_combo_box_{}", i))
                     .selected_text(face_bc.boundary_face.to_literal())
@@ -288,61 +581,75 @@ impl GuiConfig {
                             );
                         }
                     });
-                let cur_abb_value = match &face_bc.boundary_condition {
-                    BoundaryConditionGui::AntiBounceBack { value } => *value,
-                    _ => 0.0,
-                };
-                egui::ComboBox::from_id_salt(format!("boundary_condition_combo_box_{}", i))
-                    .selected_text(match &face_bc.boundary_condition {
-                        BoundaryConditionGui::AntiBounceBack { .. } => "Anti-bounce-back",
-                        BoundaryConditionGui::AntiBBNoFlux => "No-flux (ABB)",
-                        BoundaryConditionGui::BBNoFlux => "No-flux (BB)",
-                        BoundaryConditionGui::ZerothOrderNoFlux => "No-flux (Zeroth order)",
-                        BoundaryConditionGui::SecondOrderNoFlux => "No-flux (Second order)",
-                        BoundaryConditionGui::Periodic => "Periodic",
-                    })
-                    .show_ui(ui, |ui| {
-                        ui.selectable_value(
-                            &mut face_bc.boundary_condition,
-                            BoundaryConditionGui::AntiBounceBack {
-                                value: cur_abb_value,
-                            },
-                            "Anti-bounce-back",
-                        );
-                        ui.selectable_value(
-                            &mut face_bc.boundary_condition,
-                            BoundaryConditionGui::AntiBBNoFlux,
-                            "No-flux (ABB)",
-                        );
-                        ui.selectable_value(
-                            &mut face_bc.boundary_condition,
-                            BoundaryConditionGui::BBNoFlux,
-                            "No-flux (BB)",
-                        );
-                        ui.selectable_value(
-                            &mut face_bc.boundary_condition,
-                            BoundaryConditionGui::ZerothOrderNoFlux,
-                            "No-flux (Zeroth order)",
-                        );
-                        ui.selectable_value(
-                            &mut face_bc.boundary_condition,
-                            BoundaryConditionGui::SecondOrderNoFlux,
-                            "No-flux (Second order)",
-                        );
-                        ui.selectable_value(
-                            &mut face_bc.boundary_condition,
-                            BoundaryConditionGui::Periodic,
-                            "Periodic",
-                        );
-                    });
-                if let BoundaryConditionGui::AntiBounceBack { value } =
-                    &mut face_bc.boundary_condition
-                {
-                    ui.label("value:");
-                    ui.add(egui::DragValue::new(value).speed(0.01));
+                ui_boundary_condition(
+                    ui,
+                    format!("boundary_condition_combo_box_{}", i),
+                    &mut face_bc.boundary_condition,
+                );
+            });
+        }
+        ui.horizontal(|ui| {
+            ui.label("Set");
+            ui_boundary_condition(
+                ui,
+                "bulk_boundary_condition_combo_box".to_string(),
+                &mut bulk_boundary_condition,
+            );
+            if ui.button("on all faces").clicked() {
+                self.set_boundary_condition(&possible_faces, &bulk_boundary_condition);
+            }
+            let faces = possible_faces
+                .iter()
+                .zip(&selected_faces)
+                .filter(|(_, is_selected)| **is_selected)
+                .map(|(face, _)| *face)
+                .collect::<Vec<BoundaryFaceGui>>();
+            if ui
+                .add_enabled(!faces.is_empty(), egui::Button::new("on selected faces"))
+                .clicked()
+            {
+                self.set_boundary_condition(&faces, &bulk_boundary_condition);
+            }
+        });
+        ui.memory_mut(|mem| {
+            mem.data
+                .insert_temp(bulk_id, (bulk_boundary_condition, selected_faces))
+        });
+        self.ui_boundary_patches(ui, grid, dim);
+    }
+
+    fn ui_boundary_patches(&mut self, ui: &mut egui::Ui, grid: [usize; 3], dim: Dimensionality) {
+        ui.label("Patches:").on_hover_text(PATCH_LIMITATION_NOTE);
+        let mut removed = None;
+        for (i, patch_bc) in self.boundary_patches.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!("Patch {}:", i));
+                ui_face_patch(
+                    ui,
+                    format!("patch_face_combo_box_{}_{}", self.name, i),
+                    &mut patch_bc.patch,
+                    grid,
+                    dim,
+                );
+                ui_boundary_condition(
+                    ui,
+                    format!("patch_condition_combo_box_{}_{}", self.name, i),
+                    &mut patch_bc.boundary_condition,
+                );
+                if ui.button("Remove").clicked() {
+                    removed = Some(i);
                 }
             });
         }
+        if let Some(i) = removed {
+            self.boundary_patches.remove(i);
+        }
+        if ui.button("Add patch").clicked() {
+            self.boundary_patches.push(PatchBC {
+                patch: FacePatchGui::new(BoundaryFaceGui::West, grid),
+                boundary_condition: BoundaryConditionGui::AntiBBNoFlux,
+            });
+        }
     }
 
     pub(crate) fn ui_inner_boundary_condition(&mut self, ui: &mut egui::Ui) {
@@ -404,3 +711,108 @@ impl GuiConfig {
         )
     }
 }
+
+#[cfg(all(test, feature = "gui"))]
+mod tests {
+    use super::{BoundaryConditionGui, GuiConfig};
+    use crate::config::BoundaryFaceGui;
+    use crate::config::momentum::{self, FaceBC};
+
+    fn get_momentum_bcs(
+        boundary_conditions: [momentum::BoundaryConditionGui; 4],
+    ) -> Vec<momentum::FaceBC> {
+        let faces = [
+            BoundaryFaceGui::West,
+            BoundaryFaceGui::East,
+            BoundaryFaceGui::South,
+            BoundaryFaceGui::North,
+        ];
+        faces
+            .into_iter()
+            .zip(boundary_conditions)
+            .map(|(boundary_face, boundary_condition)| FaceBC {
+                boundary_face,
+                boundary_condition,
+            })
+            .collect()
+    }
+
+    fn map(
+        s_cfg: &mut GuiConfig,
+        boundary_conditions: [momentum::BoundaryConditionGui; 4],
+    ) -> Vec<BoundaryFaceGui> {
+        s_cfg.map_momentum_boundary_conditions(&get_momentum_bcs(boundary_conditions))
+    }
+
+    fn get_condition(s_cfg: &GuiConfig, boundary_face: BoundaryFaceGui) -> BoundaryConditionGui {
+        s_cfg
+            .get_face_bc(boundary_face)
+            .unwrap()
+            .boundary_condition
+            .clone()
+    }
+
+    #[test]
+    fn pressure_driven_channel() {
+        let mut s_cfg = GuiConfig::default();
+        let inlet = BoundaryConditionGui::AntiBounceBack { value: 1.0 };
+        s_cfg.set_boundary_condition(&[BoundaryFaceGui::West], &inlet);
+        let inflow_faces = map(
+            &mut s_cfg,
+            [
+                momentum::BoundaryConditionGui::AntiBounceBack { rho: 1.001 },
+                momentum::BoundaryConditionGui::AntiBounceBack { rho: 1.0 },
+                momentum::BoundaryConditionGui::NoSlip,
+                momentum::BoundaryConditionGui::NoSlip,
+            ],
+        );
+        assert!(inflow_faces == [BoundaryFaceGui::West]);
+        assert!(get_condition(&s_cfg, BoundaryFaceGui::West) == inlet);
+        assert!(
+            get_condition(&s_cfg, BoundaryFaceGui::East) == BoundaryConditionGui::ZerothOrderNoFlux
+        );
+        for boundary_face in [BoundaryFaceGui::South, BoundaryFaceGui::North] {
+            assert!(get_condition(&s_cfg, boundary_face) == BoundaryConditionGui::AntiBBNoFlux);
+        }
+    }
+
+    #[test]
+    fn velocity_inlet_moving_wall_and_periodic_faces() {
+        let mut s_cfg = GuiConfig::default();
+        let velocity = |ux| momentum::BoundaryConditionGui::BounceBack {
+            rho: 1.0,
+            ux,
+            uy: 0.0,
+            uz: 0.0,
+        };
+        let inflow_faces = map(
+            &mut s_cfg,
+            [
+                momentum::BoundaryConditionGui::Periodic,
+                momentum::BoundaryConditionGui::Periodic,
+                velocity(0.0),
+                velocity(0.1),
+            ],
+        );
+        assert!(inflow_faces.is_empty());
+        for boundary_face in [BoundaryFaceGui::West, BoundaryFaceGui::East] {
+            assert!(get_condition(&s_cfg, boundary_face) == BoundaryConditionGui::Periodic);
+        }
+        for boundary_face in [BoundaryFaceGui::South, BoundaryFaceGui::North] {
+            assert!(get_condition(&s_cfg, boundary_face) == BoundaryConditionGui::AntiBBNoFlux);
+        }
+        let inflow_faces = map(
+            &mut s_cfg,
+            [
+                velocity(0.05),
+                velocity(0.05),
+                momentum::BoundaryConditionGui::NoSlip,
+                momentum::BoundaryConditionGui::NoSlip,
+            ],
+        );
+        assert!(inflow_faces == [BoundaryFaceGui::West]);
+        assert!(
+            get_condition(&s_cfg, BoundaryFaceGui::East) == BoundaryConditionGui::ZerothOrderNoFlux
+        );
+    }
+}
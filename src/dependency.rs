@@ -0,0 +1,225 @@
+use std::path::{Component, Path, PathBuf};
+
+use crate::config::{CargoGuiConfig, LBFLOW_SOA_VERSION, ParModeGui, UNSAFE_FASTPATH_FEATURE};
+
+pub(crate) struct SourceCodeInfo {
+    pub(crate) resolved_path: PathBuf,
+    pub(crate) version: String,
+    pub(crate) features: Vec<String>,
+}
+
+/// Resolves the source code path relative to the case directory, as cargo
+/// does, and reads the package name, version and features of its manifest.
+pub(crate) fn inspect_source_code(
+    case_dir: &Path,
+    source_code_path: &str,
+) -> Result<SourceCodeInfo, String> {
+    let resolved_path = case_dir.join(source_code_path);
+    let resolved_path = resolved_path
+        .canonicalize()
+        .map_err(|_| format!("{} does not exist", resolved_path.display()))?;
+    let manifest_path = resolved_path.join("Cargo.toml");
+    let content = std::fs::read_to_string(&manifest_path)
+        .map_err(|_| format!("No Cargo.toml in {}", resolved_path.display()))?;
+    let manifest = content
+        .parse::<toml::Table>()
+        .map_err(|e| format!("Invalid {}: {}", manifest_path.display(), e))?;
+    let package = manifest
+        .get("package")
+        .and_then(|package| package.as_table())
+        .ok_or_else(|| format!("{} has no [package]", manifest_path.display()))?;
+    let name = package
+        .get("name")
+        .and_then(|name| name.as_str())
+        .unwrap_or_default();
+    if name != "lbflow_soa" {
+        return Err(format!(
+            "{} is the package \"{}\", not \"lbflow_soa\"",
+            resolved_path.display(),
+            name
+        ));
+    }
+    let version = package
+        .get("version")
+        .and_then(|version| version.as_str())
+        .unwrap_or_default()
+        .to_string();
+    let features = manifest
+        .get("features")
+        .and_then(|features| features.as_table())
+        .map(|features| features.keys().cloned().collect())
+        .unwrap_or_default();
+    Ok(SourceCodeInfo {
+        resolved_path,
+        version,
+        features,
+    })
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            _ => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Returns the path of `to` relative to the directory `from_dir`, written with
+/// forward slashes. Neither path has to exist; both are made absolute against
+/// the working directory. Returns `None` when they share no root (e.g. two
+/// drives on Windows).
+pub(crate) fn get_relative_path(from_dir: &Path, to: &Path) -> Option<String> {
+    let from_dir = normalize(&std::path::absolute(from_dir).ok()?);
+    let to = normalize(&std::path::absolute(to).ok()?);
+    let n_common = from_dir
+        .components()
+        .zip(to.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+    if n_common == 0 {
+        return None;
+    }
+    let mut parts = vec![];
+    for _ in from_dir.components().skip(n_common) {
+        parts.push("..".to_string());
+    }
+    for component in to.components().skip(n_common) {
+        parts.push(component.as_os_str().to_string_lossy().into_owned());
+    }
+    if parts.is_empty() {
+        return Some(".".to_string());
+    }
+    Some(parts.join("/"))
+}
+
+/// Splits a semantic version into its numbers and its pre-release part,
+/// ignoring build metadata.
+fn parse_version(version: &str) -> Option<([u64; 3], Option<&str>)> {
+    let version = version.trim().split('+').next()?;
+    let (numbers, pre_release) = match version.split_once('-') {
+        Some((numbers, pre_release)) => (numbers, Some(pre_release)),
+        None => (version, None),
+    };
+    let mut parts = numbers.split('.').map(|part| part.parse::<u64>().ok());
+    let numbers = [parts.next()??, parts.next()??, parts.next()??];
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((numbers, pre_release))
+}
+
+/// Orders pre-release identifiers as semver does: numeric identifiers
+/// numerically and below alphanumeric ones, and a shorter list first when
+/// it is a prefix of the other.
+fn compare_pre_releases(a: &str, b: &str) -> std::cmp::Ordering {
+    let mut a_identifiers = a.split('.');
+    let mut b_identifiers = b.split('.');
+    loop {
+        let ordering = match (a_identifiers.next(), b_identifiers.next()) {
+            (None, None) => return std::cmp::Ordering::Equal,
+            (None, Some(_)) => return std::cmp::Ordering::Less,
+            (Some(_), None) => return std::cmp::Ordering::Greater,
+            (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                (Ok(_), Err(_)) => std::cmp::Ordering::Less,
+                (Err(_), Ok(_)) => std::cmp::Ordering::Greater,
+                (Err(_), Err(_)) => a.cmp(b),
+            },
+        };
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+}
+
+/// Whether `version` satisfies the caret requirement `requirement`, which is
+/// how cargo reads a bare `version = "..."` in a dependency. As in cargo, a
+/// pre-release version only satisfies a pre-release requirement on the same
+/// major, minor and patch numbers.
+pub(crate) fn is_version_compatible(version: &str, requirement: &str) -> bool {
+    let (Some((version, version_pre)), Some((requirement, requirement_pre))) =
+        (parse_version(version), parse_version(requirement))
+    else {
+        return false;
+    };
+    let same_series = match requirement {
+        [0, 0, patch] => version[..2] == [0, 0] && version[2] == patch,
+        [0, minor, _] => version[..2] == [0, minor],
+        [major, _, _] => version[0] == major,
+    };
+    match (version_pre, requirement_pre) {
+        (None, _) => same_series && version >= requirement,
+        (Some(version_pre), Some(requirement_pre)) => {
+            version == requirement && compare_pre_releases(version_pre, requirement_pre).is_ge()
+        }
+        (Some(_), None) => false,
+    }
+}
+
+impl SourceCodeInfo {
+    pub(crate) fn get_warnings(&self, c_cfg: &CargoGuiConfig) -> Vec<String> {
+        let mut warnings = vec![];
+        if !is_version_compatible(&self.version, LBFLOW_SOA_VERSION) {
+            warnings.push(format!(
+                "lbflow_soa {} does not match the required version {}",
+                self.version, LBFLOW_SOA_VERSION
+            ));
+        }
+        if c_cfg.par_mode == ParModeGui::Unsafe
+            && !self
+                .features
+                .iter()
+                .any(|feature| feature == UNSAFE_FASTPATH_FEATURE)
+        {
+            warnings.push(format!(
+                "lbflow_soa {} has no \"{}\" feature",
+                self.version, UNSAFE_FASTPATH_FEATURE
+            ));
+        }
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_version_compatible;
+
+    #[test]
+    fn caret_requirements() {
+        assert!(is_version_compatible("1.2.3", "1.2.3"));
+        assert!(is_version_compatible("1.9.0", "1.2.3"));
+        assert!(!is_version_compatible("1.2.2", "1.2.3"));
+        assert!(!is_version_compatible("2.0.0", "1.2.3"));
+        assert!(is_version_compatible("0.1.7", "0.1.0"));
+        assert!(!is_version_compatible("0.2.0", "0.1.0"));
+        assert!(is_version_compatible("0.0.3", "0.0.3"));
+        assert!(!is_version_compatible("0.0.4", "0.0.3"));
+    }
+
+    #[test]
+    fn pre_release_versions() {
+        assert!(!is_version_compatible("0.1.0-alpha", "0.1.0"));
+        assert!(!is_version_compatible("0.1.1-alpha", "0.1.0"));
+        assert!(is_version_compatible("0.1.0", "0.1.0-alpha"));
+        assert!(is_version_compatible("0.1.0-beta", "0.1.0-alpha"));
+        assert!(is_version_compatible("0.1.0-alpha.10", "0.1.0-alpha.2"));
+        assert!(!is_version_compatible("0.1.0-alpha", "0.1.0-alpha.1"));
+        assert!(!is_version_compatible("0.1.0-alpha.1", "0.1.0-alpha.beta"));
+        assert!(!is_version_compatible("0.1.1-beta", "0.1.0-alpha"));
+    }
+
+    #[test]
+    fn build_metadata_and_invalid_versions() {
+        assert!(is_version_compatible("0.1.0+build.5", "0.1.0"));
+        assert!(!is_version_compatible("0.1", "0.1.0"));
+        assert!(!is_version_compatible("0.1.0.1", "0.1.0"));
+        assert!(!is_version_compatible("v0.1.0", "0.1.0"));
+        assert!(!is_version_compatible("0.1.0", ""));
+    }
+}
@@ -0,0 +1,97 @@
+use std::time::Duration;
+
+use crate::config::*;
+
+const F64_SIZE: u64 = 8;
+/// Populations are stored twice, before and after streaming.
+const N_POPULATION_COPIES: u64 = 2;
+
+pub(crate) struct MemoryEstimate {
+    pub(crate) n_nodes: u64,
+    pub(crate) population_bytes: u64,
+    pub(crate) macroscopic_bytes: u64,
+}
+
+impl MemoryEstimate {
+    pub(crate) fn get_total_bytes(&self) -> u64 {
+        self.population_bytes + self.macroscopic_bytes
+    }
+}
+
+pub(crate) fn estimate_memory(
+    grid: [usize; 3],
+    dim: Dimensionality,
+    m_velocity_set: &VelocitySetGui,
+    s_velocity_sets: &[VelocitySetGui],
+) -> MemoryEstimate {
+    let n_nodes = match dim {
+        Dimensionality::D2 => grid[0] as u64 * grid[1] as u64,
+        Dimensionality::D3 => grid[0] as u64 * grid[1] as u64 * grid[2] as u64,
+    };
+    let n_velocity_components = match dim {
+        Dimensionality::D2 => 2,
+        Dimensionality::D3 => 3,
+    };
+    let n_populations = std::iter::once(m_velocity_set)
+        .chain(s_velocity_sets)
+        .map(|velocity_set| velocity_set.get_velocities().len() as u64)
+        .sum::<u64>();
+    // Density and velocity for the momentum, one value per scalar.
+    let n_macroscopic_fields = 1 + n_velocity_components + s_velocity_sets.len() as u64;
+    MemoryEstimate {
+        n_nodes,
+        population_bytes: n_nodes * n_populations * N_POPULATION_COPIES * F64_SIZE,
+        macroscopic_bytes: n_nodes * n_macroscopic_fields * F64_SIZE,
+    }
+}
+
+/// Reads `MemAvailable` from `/proc/meminfo`, in bytes.
+pub(crate) fn get_available_memory() -> Option<u64> {
+    let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+    let line = meminfo
+        .lines()
+        .find(|line| line.starts_with("MemAvailable:"))?;
+    let kilobytes = line.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+    Some(kilobytes * 1024)
+}
+
+pub(crate) fn estimate_wall_time(n_nodes: u64, n_steps: u64, mlups: f64) -> Option<Duration> {
+    if mlups <= 0.0 {
+        return None;
+    }
+    let seconds = n_nodes as f64 * n_steps as f64 / (mlups * 1.0e6);
+    Duration::try_from_secs_f64(seconds).ok()
+}
+
+pub(crate) fn measure_mlups(n_nodes: u64, n_steps: u64, elapsed: Duration) -> Option<f64> {
+    let seconds = elapsed.as_secs_f64();
+    if seconds <= 0.0 || n_steps == 0 {
+        return None;
+    }
+    Some(n_nodes as f64 * n_steps as f64 / seconds / 1.0e6)
+}
+
+pub(crate) fn get_bytes_literal(bytes: u64) -> String {
+    let units = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < units.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, units[unit])
+}
+
+pub(crate) fn get_duration_literal(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (days, hours, minutes) = (seconds / 86400, seconds / 3600 % 24, seconds / 60 % 60);
+    if days > 0 {
+        format!("{} d {} h {} min", days, hours, minutes)
+    } else if hours > 0 {
+        format!("{} h {} min", hours, minutes)
+    } else if minutes > 0 {
+        format!("{} min {} s", minutes, seconds % 60)
+    } else {
+        format!("{:.1} s", duration.as_secs_f64())
+    }
+}
@@ -0,0 +1,430 @@
+#[derive(Clone, Copy, PartialEq)]
+enum Token {
+    Number(f64),
+    Identifier(usize, usize),
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Caret,
+    LeftParen,
+    RightParen,
+    Comma,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Function {
+    Sin,
+    Cos,
+    Tan,
+    Asin,
+    Acos,
+    Atan,
+    Sinh,
+    Cosh,
+    Tanh,
+    Exp,
+    Ln,
+    Log10,
+    Sqrt,
+    Abs,
+    Floor,
+    Ceil,
+    Step,
+    Min,
+    Max,
+    Pow,
+    Atan2,
+}
+
+impl Function {
+    fn from_name(name: &str) -> Option<(Self, usize)> {
+        let function = match name {
+            "sin" => (Function::Sin, 1),
+            "cos" => (Function::Cos, 1),
+            "tan" => (Function::Tan, 1),
+            "asin" => (Function::Asin, 1),
+            "acos" => (Function::Acos, 1),
+            "atan" => (Function::Atan, 1),
+            "sinh" => (Function::Sinh, 1),
+            "cosh" => (Function::Cosh, 1),
+            "tanh" => (Function::Tanh, 1),
+            "exp" => (Function::Exp, 1),
+            "ln" => (Function::Ln, 1),
+            "log10" => (Function::Log10, 1),
+            "sqrt" => (Function::Sqrt, 1),
+            "abs" => (Function::Abs, 1),
+            "floor" => (Function::Floor, 1),
+            "ceil" => (Function::Ceil, 1),
+            "step" => (Function::Step, 1),
+            "min" => (Function::Min, 2),
+            "max" => (Function::Max, 2),
+            "pow" => (Function::Pow, 2),
+            "atan2" => (Function::Atan2, 2),
+            _ => return None,
+        };
+        Some(function)
+    }
+
+    fn apply(self, args: &[f64]) -> f64 {
+        match self {
+            Function::Sin => args[0].sin(),
+            Function::Cos => args[0].cos(),
+            Function::Tan => args[0].tan(),
+            Function::Asin => args[0].asin(),
+            Function::Acos => args[0].acos(),
+            Function::Atan => args[0].atan(),
+            Function::Sinh => args[0].sinh(),
+            Function::Cosh => args[0].cosh(),
+            Function::Tanh => args[0].tanh(),
+            Function::Exp => args[0].exp(),
+            Function::Ln => args[0].ln(),
+            Function::Log10 => args[0].log10(),
+            Function::Sqrt => args[0].sqrt(),
+            Function::Abs => args[0].abs(),
+            Function::Floor => args[0].floor(),
+            Function::Ceil => args[0].ceil(),
+            Function::Step => {
+                if args[0] >= 0.0 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Function::Min => args[0].min(args[1]),
+            Function::Max => args[0].max(args[1]),
+            Function::Pow => args[0].powf(args[1]),
+            Function::Atan2 => args[0].atan2(args[1]),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub(crate) enum Expression {
+    Number(f64),
+    Variable(usize),
+    Negate(Box<Expression>),
+    Add(Box<Expression>, Box<Expression>),
+    Subtract(Box<Expression>, Box<Expression>),
+    Multiply(Box<Expression>, Box<Expression>),
+    Divide(Box<Expression>, Box<Expression>),
+    Power(Box<Expression>, Box<Expression>),
+    Call(Function, Vec<Expression>),
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let bytes = input.as_bytes();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        let token = match c {
+            b' ' | b'\t' => {
+                i += 1;
+                continue;
+            }
+            b'+' => Token::Plus,
+            b'-' => Token::Minus,
+            b'*' => Token::Star,
+            b'/' => Token::Slash,
+            b'^' => Token::Caret,
+            b'(' => Token::LeftParen,
+            b')' => Token::RightParen,
+            b',' => Token::Comma,
+            b'0'..=b'9' | b'.' => {
+                let start = i;
+                while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b'.') {
+                    i += 1;
+                }
+                if i < bytes.len() && (bytes[i] == b'e' || bytes[i] == b'E') {
+                    let mut j = i + 1;
+                    if j < bytes.len() && (bytes[j] == b'+' || bytes[j] == b'-') {
+                        j += 1;
+                    }
+                    if j < bytes.len() && bytes[j].is_ascii_digit() {
+                        i = j;
+                        while i < bytes.len() && bytes[i].is_ascii_digit() {
+                            i += 1;
+                        }
+                    }
+                }
+                let literal = &input[start..i];
+                let value = literal
+                    .parse::<f64>()
+                    .map_err(|_| format!("Invalid number \"{}\"", literal))?;
+                tokens.push(Token::Number(value));
+                continue;
+            }
+            c if c.is_ascii_alphabetic() || c == b'_' => {
+                let start = i;
+                while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                    i += 1;
+                }
+                tokens.push(Token::Identifier(start, i));
+                continue;
+            }
+            _ => {
+                return Err(format!(
+                    "Unexpected character '{}'",
+                    input[i..].chars().next().unwrap_or('?')
+                ));
+            }
+        };
+        tokens.push(token);
+        i += 1;
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    tokens: Vec<Token>,
+    position: usize,
+    variables: &'a [&'a str],
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.position).copied()
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek();
+        self.position += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token, literal: &str) -> Result<(), String> {
+        if self.next() == Some(expected) {
+            Ok(())
+        } else {
+            Err(format!("Expected '{}'", literal))
+        }
+    }
+
+    fn parse_sum(&mut self) -> Result<Expression, String> {
+        let mut lhs = self.parse_product()?;
+        loop {
+            match self.peek() {
+                Some(Token::Plus) => {
+                    self.next();
+                    lhs = Expression::Add(Box::new(lhs), Box::new(self.parse_product()?));
+                }
+                Some(Token::Minus) => {
+                    self.next();
+                    lhs = Expression::Subtract(Box::new(lhs), Box::new(self.parse_product()?));
+                }
+                _ => return Ok(lhs),
+            }
+        }
+    }
+
+    fn parse_product(&mut self) -> Result<Expression, String> {
+        let mut lhs = self.parse_unary()?;
+        loop {
+            match self.peek() {
+                Some(Token::Star) => {
+                    self.next();
+                    lhs = Expression::Multiply(Box::new(lhs), Box::new(self.parse_unary()?));
+                }
+                Some(Token::Slash) => {
+                    self.next();
+                    lhs = Expression::Divide(Box::new(lhs), Box::new(self.parse_unary()?));
+                }
+                _ => return Ok(lhs),
+            }
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Expression, String> {
+        match self.peek() {
+            Some(Token::Minus) => {
+                self.next();
+                Ok(Expression::Negate(Box::new(self.parse_unary()?)))
+            }
+            Some(Token::Plus) => {
+                self.next();
+                self.parse_unary()
+            }
+            _ => self.parse_power(),
+        }
+    }
+
+    fn parse_power(&mut self) -> Result<Expression, String> {
+        let base = self.parse_atom()?;
+        if self.peek() == Some(Token::Caret) {
+            self.next();
+            let exponent = self.parse_unary()?;
+            return Ok(Expression::Power(Box::new(base), Box::new(exponent)));
+        }
+        Ok(base)
+    }
+
+    fn parse_atom(&mut self) -> Result<Expression, String> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(Expression::Number(value)),
+            Some(Token::LeftParen) => {
+                let inner = self.parse_sum()?;
+                self.expect(Token::RightParen, ")")?;
+                Ok(inner)
+            }
+            Some(Token::Identifier(start, end)) => {
+                let name = &self.input[start..end];
+                if self.peek() == Some(Token::LeftParen) {
+                    self.next();
+                    let (function, n_args) = Function::from_name(name)
+                        .ok_or_else(|| format!("Unknown function \"{}\"", name))?;
+                    let mut args = vec![self.parse_sum()?];
+                    while self.peek() == Some(Token::Comma) {
+                        self.next();
+                        args.push(self.parse_sum()?);
+                    }
+                    self.expect(Token::RightParen, ")")?;
+                    if args.len() != n_args {
+                        return Err(format!(
+                            "Function \"{}\" takes {} argument(s)",
+                            name, n_args
+                        ));
+                    }
+                    return Ok(Expression::Call(function, args));
+                }
+                if let Some(index) = self.variables.iter().position(|v| *v == name) {
+                    return Ok(Expression::Variable(index));
+                }
+                match name {
+                    "pi" => Ok(Expression::Number(std::f64::consts::PI)),
+                    "e" => Ok(Expression::Number(std::f64::consts::E)),
+                    _ => Err(format!("Unknown variable \"{}\"", name)),
+                }
+            }
+            Some(_) => Err("Unexpected symbol".to_string()),
+            None => Err("Unexpected end of expression".to_string()),
+        }
+    }
+}
+
+impl Expression {
+    /// Parses an arithmetic expression with `+ - * / ^`, parentheses, the
+    /// constants `pi` and `e`, the usual elementary functions and the given
+    /// variable names. Variables are evaluated by position in `variables`.
+    pub(crate) fn parse(input: &str, variables: &[&str]) -> Result<Self, String> {
+        let mut parser = Parser {
+            input,
+            tokens: tokenize(input)?,
+            position: 0,
+            variables,
+        };
+        if parser.tokens.is_empty() {
+            return Err("Empty expression".to_string());
+        }
+        let expression = parser.parse_sum()?;
+        if parser.position < parser.tokens.len() {
+            return Err("Unexpected symbol after the end of the expression".to_string());
+        }
+        Ok(expression)
+    }
+
+    pub(crate) fn evaluate(&self, values: &[f64]) -> f64 {
+        match self {
+            Expression::Number(value) => *value,
+            Expression::Variable(index) => values[*index],
+            Expression::Negate(inner) => -inner.evaluate(values),
+            Expression::Add(lhs, rhs) => lhs.evaluate(values) + rhs.evaluate(values),
+            Expression::Subtract(lhs, rhs) => lhs.evaluate(values) - rhs.evaluate(values),
+            Expression::Multiply(lhs, rhs) => lhs.evaluate(values) * rhs.evaluate(values),
+            Expression::Divide(lhs, rhs) => lhs.evaluate(values) / rhs.evaluate(values),
+            Expression::Power(lhs, rhs) => lhs.evaluate(values).powf(rhs.evaluate(values)),
+            Expression::Call(function, args) => {
+                let args = args
+                    .iter()
+                    .map(|arg| arg.evaluate(values))
+                    .collect::<Vec<f64>>();
+                function.apply(&args)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Expression;
+
+    fn evaluate(input: &str) -> f64 {
+        Expression::parse(input, &["x", "y"])
+            .unwrap_or_else(|e| panic!("\"{}\" failed to parse: {}", input, e))
+            .evaluate(&[3.0, 2.0])
+    }
+
+    fn parse_error(input: &str) -> String {
+        match Expression::parse(input, &["x", "y"]) {
+            Ok(_) => panic!("\"{}\" parsed", input),
+            Err(e) => e,
+        }
+    }
+
+    #[test]
+    fn operator_precedence() {
+        assert_eq!(evaluate("1 + 2 * 3"), 7.0);
+        assert_eq!(evaluate("(1 + 2) * 3"), 9.0);
+        assert_eq!(evaluate("8 - 4 - 2"), 2.0);
+        assert_eq!(evaluate("8 / 4 / 2"), 1.0);
+        assert_eq!(evaluate("2 * 3 ^ 2"), 18.0);
+        assert_eq!(evaluate("x * y + 1"), 7.0);
+    }
+
+    #[test]
+    fn unary_minus_and_power() {
+        assert_eq!(evaluate("-x^2"), -9.0);
+        assert_eq!(evaluate("(-x)^2"), 9.0);
+        assert_eq!(evaluate("2^-1"), 0.5);
+        assert_eq!(evaluate("2^3^2"), 512.0);
+        assert_eq!(evaluate("--x"), 3.0);
+        assert_eq!(evaluate("+x - -y"), 5.0);
+    }
+
+    #[test]
+    fn scientific_notation() {
+        assert_eq!(evaluate("1e3"), 1000.0);
+        assert_eq!(evaluate("2.5E-1"), 0.25);
+        assert_eq!(evaluate("1e+2"), 100.0);
+        assert_eq!(evaluate("2*e"), 2.0 * std::f64::consts::E);
+        assert_eq!(
+            parse_error("1e"),
+            "Unexpected symbol after the end of the expression"
+        );
+    }
+
+    #[test]
+    fn function_arity() {
+        assert_eq!(evaluate("max(x, y)"), 3.0);
+        assert_eq!(evaluate("pow(y, x)"), 8.0);
+        assert_eq!(
+            parse_error("sin(x, y)"),
+            "Function \"sin\" takes 1 argument(s)"
+        );
+        assert_eq!(
+            parse_error("min(x)"),
+            "Function \"min\" takes 2 argument(s)"
+        );
+    }
+
+    #[test]
+    fn unknown_identifiers() {
+        assert_eq!(parse_error("z + 1"), "Unknown variable \"z\"");
+        assert_eq!(parse_error("foo(x)"), "Unknown function \"foo\"");
+    }
+
+    #[test]
+    fn trailing_tokens() {
+        assert_eq!(
+            parse_error("x y"),
+            "Unexpected symbol after the end of the expression"
+        );
+        assert_eq!(
+            parse_error("(x + 1))"),
+            "Unexpected symbol after the end of the expression"
+        );
+        assert_eq!(parse_error("x +"), "Unexpected end of expression");
+        assert_eq!(parse_error(""), "Empty expression");
+    }
+}
@@ -0,0 +1,174 @@
+use crate::config::FacePatchGui;
+use crate::config::momentum;
+use crate::metadata::CaseMetadata;
+use crate::templates::CaseTemplate;
+
+impl CaseTemplate {
+    /// Returns the path (relative to the case directory) and content of every
+    /// file of the case.
+    pub(crate) fn get_case_files(&self) -> Result<Vec<(String, String)>, String> {
+        let mut files = vec![
+            ("Cargo.toml".to_string(), self.c_cfg.get_cargo_toml()),
+            ("src/main.rs".to_string(), self.get_main_rs_content()),
+        ];
+        files.extend(self.c_cfg.get_cargo_config_file());
+        files.extend(self.j_cfg.get_job_script_file(&self.c_cfg.case_name));
+        files.extend(self.get_generated_files()?);
+        files.extend(CaseMetadata::new(self.clone()).get_files()?);
+        Ok(files)
+    }
+
+    fn get_generated_files(&self) -> Result<Vec<(String, String)>, String> {
+        let grid = self.d_cfg.grid;
+        let mut files = vec![];
+        self.validate_boundary_patches()?;
+        files.extend(
+            self.d_cfg
+                .get_map_file(self.m_cfg.delta_x, &self.get_face_patches())?,
+        );
+        files.extend(self.m_cfg.get_initial_field_files(grid, &self.d_cfg.dim)?);
+        for s_cfg in &self.s_cfg_vec {
+            files.extend(s_cfg.get_initial_field_files(grid, self.m_cfg.delta_x)?);
+        }
+        Ok(files)
+    }
+
+    fn get_face_patches(&self) -> Vec<&FacePatchGui> {
+        let mut patches = self
+            .m_cfg
+            .boundary_patches
+            .iter()
+            .map(|patch_bc| &patch_bc.patch)
+            .collect::<Vec<&FacePatchGui>>();
+        for s_cfg in &self.s_cfg_vec {
+            patches.extend(
+                s_cfg
+                    .boundary_patches
+                    .iter()
+                    .map(|patch_bc| &patch_bc.patch),
+            );
+        }
+        patches
+    }
+
+    /// Checks that the patches can be generated. The rest of a patched face
+    /// becomes solid wall nodes of the shared node map, so every field must
+    /// already have a wall condition on that face: no-slip for the momentum
+    /// and no-flux for the scalars. For the same reason, every field must
+    /// patch the face with the same ranges.
+    pub(crate) fn validate_boundary_patches(&self) -> Result<(), String> {
+        let patches = self.get_face_patches();
+        let mut patched_faces = vec![];
+        for patch in &patches {
+            patch.validate(self.d_cfg.grid, self.d_cfg.dim)?;
+            if !patched_faces.contains(&patch.boundary_face) {
+                patched_faces.push(patch.boundary_face);
+            }
+        }
+        let get_face_ranges = |patches: Vec<&FacePatchGui>, boundary_face| {
+            let mut ranges = patches
+                .into_iter()
+                .filter(|patch| patch.boundary_face == boundary_face)
+                .map(|patch| patch.ranges)
+                .collect::<Vec<[[usize; 2]; 2]>>();
+            ranges.sort();
+            ranges.dedup();
+            ranges
+        };
+        for boundary_face in patched_faces {
+            let face_literal = boundary_face.to_literal();
+            let m_ranges = get_face_ranges(
+                self.m_cfg
+                    .boundary_patches
+                    .iter()
+                    .map(|patch_bc| &patch_bc.patch)
+                    .collect(),
+                boundary_face,
+            );
+            let is_m_wall = self
+                .m_cfg
+                .get_face_bc(boundary_face)
+                .is_some_and(|face_bc| {
+                    face_bc.boundary_condition == momentum::BoundaryConditionGui::NoSlip
+                });
+            if !is_m_wall {
+                return Err(format!(
+                    "The {} face has patches, so the rest of it becomes solid wall nodes: its momentum condition must be No-slip",
+                    face_literal
+                ));
+            }
+            for s_cfg in &self.s_cfg_vec {
+                let is_s_wall = s_cfg
+                    .get_face_bc(boundary_face)
+                    .is_some_and(|face_bc| face_bc.boundary_condition.is_no_flux());
+                if !is_s_wall {
+                    return Err(format!(
+                        "The {} face has patches, so the rest of it becomes solid wall nodes: its {} condition must be a no-flux one",
+                        face_literal, s_cfg.name
+                    ));
+                }
+                let s_ranges = get_face_ranges(
+                    s_cfg
+                        .boundary_patches
+                        .iter()
+                        .map(|patch_bc| &patch_bc.patch)
+                        .collect(),
+                    boundary_face,
+                );
+                if s_ranges != m_ranges {
+                    return Err(format!(
+                        "The patches of {} on the {} face differ from the momentum ones, but the fields share one node map: every field must patch the face with the same ranges",
+                        s_cfg.name, face_literal
+                    ));
+                }
+            }
+        }
+        self.m_cfg.validate_boundary_patches()?;
+        for s_cfg in &self.s_cfg_vec {
+            s_cfg.validate_boundary_patches()?;
+        }
+        Ok(())
+    }
+
+    fn get_s_params_vec_content(&self) -> String {
+        let mut s_params_contents = vec![];
+        for s_cfg in &self.s_cfg_vec {
+            let s_params_content = s_cfg.get_s_params_content();
+            s_params_contents.push(s_params_content);
+        }
+        s_params_contents.join("\n")
+    }
+
+    fn get_solve_content(&self) -> String {
+        let s_params_vec_literal = self
+            .s_cfg_vec
+            .iter()
+            .map(|ps_cfg| ps_cfg.get_s_params_name_literal())
+            .collect::<Vec<String>>()
+            .join(", ");
+        format!(
+            "    solve(domain, m_params, vec![{}], vec![]);",
+            s_params_vec_literal
+        )
+    }
+
+    fn get_main_rs_content(&self) -> String {
+        let domain_content = self
+            .d_cfg
+            .get_domain_content(!self.get_face_patches().is_empty());
+        let m_params_content = self.m_cfg.get_m_params_content(&self.d_cfg.dim);
+        let s_params_vec_content = self.get_s_params_vec_content();
+        let solve_content = self.get_solve_content();
+        format!(
+            r#"use lbflow_soa::prelude::*;
+
+fn main() {{
+{domain_content}
+{m_params_content}
+{s_params_vec_content}
+{solve_content}
+}}
+"#
+        )
+    }
+}
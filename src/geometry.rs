@@ -0,0 +1,570 @@
+#[cfg(feature = "gui")]
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "gui")]
+use crate::config::VelocitySetGui;
+use crate::config::evaluate_expressions_on_grid;
+#[cfg(feature = "gui")]
+use crate::config::momentum::{self, BoundaryConditionGui};
+use crate::config::{BoundaryFaceGui, CoordinatesGui, Dimensionality, FacePatchGui};
+
+pub(crate) struct NodeTypeMap {
+    pub(crate) grid: [usize; 3],
+    pub(crate) solid: Vec<bool>,
+}
+
+impl NodeTypeMap {
+    pub(crate) fn new_fluid(grid: [usize; 3]) -> Self {
+        NodeTypeMap {
+            grid,
+            solid: vec![false; grid[0] * grid[1] * grid[2]],
+        }
+    }
+
+    /// Marks as solid every node where the expression is positive.
+    pub(crate) fn from_expression(
+        solid: &str,
+        grid: [usize; 3],
+        coordinates: CoordinatesGui,
+        delta_x: f64,
+    ) -> Result<Self, String> {
+        let rows = evaluate_expressions_on_grid(&[solid], grid, coordinates, delta_x)?;
+        Ok(NodeTypeMap {
+            grid,
+            solid: rows.iter().map(|row| row[0] > 0.0).collect(),
+        })
+    }
+
+    #[cfg(feature = "gui")]
+    pub(crate) fn get_index(&self, x: usize, y: usize, z: usize) -> usize {
+        x + self.grid[0] * (y + self.grid[1] * z)
+    }
+
+    pub(crate) fn get_coords(&self, index: usize) -> [usize; 3] {
+        let x = index % self.grid[0];
+        let y = (index / self.grid[0]) % self.grid[1];
+        let z = index / (self.grid[0] * self.grid[1]);
+        [x, y, z]
+    }
+
+    #[cfg(feature = "gui")]
+    pub(crate) fn is_solid(&self, x: usize, y: usize, z: usize) -> bool {
+        self.solid[self.get_index(x, y, z)]
+    }
+
+    #[cfg(feature = "gui")]
+    /// Reads a map file with one node per line, x running fastest. The last
+    /// comma-separated field of each line is the node type (0 fluid, 1 solid);
+    /// when there are more fields, the leading ones are the node coordinates.
+    /// Lines that do not end in a number (e.g. a header) are skipped.
+    pub(crate) fn from_file(path: &Path, grid: [usize; 3]) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let mut map = NodeTypeMap::new_fluid(grid);
+        let mut n_read = 0;
+        for line in content.lines() {
+            let Some((index, node_type)) = map.parse_file_line(line, n_read)? else {
+                continue;
+            };
+            map.solid[index] = node_type != 0;
+            n_read += 1;
+        }
+        if n_read != map.solid.len() {
+            return Err(format!(
+                "Map file has {} nodes, the grid has {}",
+                n_read,
+                map.solid.len()
+            ));
+        }
+        Ok(map)
+    }
+
+    #[cfg(feature = "gui")]
+    /// Returns the node index and type of a map file line, or `None` for a
+    /// line that does not end in a number. `n_read` is the number of node
+    /// lines before this one, used when the line has no coordinates.
+    fn parse_file_line(&self, line: &str, n_read: usize) -> Result<Option<(usize, i64)>, String> {
+        let fields = line.split(',').map(str::trim).collect::<Vec<&str>>();
+        let Some(Ok(node_type)) = fields.last().map(|f| f.parse::<i64>()) else {
+            return Ok(None);
+        };
+        let index = if fields.len() > 1 {
+            let mut coords = [0_usize; 3];
+            for (coord, field) in coords.iter_mut().zip(&fields[..fields.len() - 1]) {
+                *coord = field
+                    .parse::<usize>()
+                    .map_err(|_| format!("Invalid coordinate in line \"{}\"", line))?;
+            }
+            if (0..3).any(|i| coords[i] >= self.grid[i]) {
+                return Err(format!("Node out of the grid in line \"{}\"", line));
+            }
+            self.get_index(coords[0], coords[1], coords[2])
+        } else {
+            n_read
+        };
+        if index >= self.solid.len() {
+            return Err("Map file has more nodes than the grid".to_string());
+        }
+        Ok(Some((index, node_type)))
+    }
+
+    /// Turns into solid nodes the boundary nodes of each patched face that lie
+    /// outside all of its patches, so that only the patches stay open.
+    pub(crate) fn close_faces_outside_patches(
+        &mut self,
+        patches: &[&FacePatchGui],
+        dim: Dimensionality,
+    ) {
+        for boundary_face in BoundaryFaceGui::get_faces(dim) {
+            let face_patches = patches
+                .iter()
+                .filter(|patch| patch.boundary_face == boundary_face)
+                .collect::<Vec<_>>();
+            if face_patches.is_empty() {
+                continue;
+            }
+            let axis = boundary_face.get_axis();
+            let face_coord = if boundary_face.is_min_side() {
+                0
+            } else {
+                self.grid[axis] - 1
+            };
+            for index in 0..self.solid.len() {
+                let coords = self.get_coords(index);
+                if coords[axis] == face_coord
+                    && !face_patches.iter().any(|patch| patch.contains(coords, dim))
+                {
+                    self.solid[index] = true;
+                }
+            }
+        }
+    }
+
+    pub(crate) fn get_file_content(&self) -> String {
+        let mut content = String::with_capacity(2 * self.solid.len());
+        for solid in &self.solid {
+            content.push_str(if *solid { "1\n" } else { "0\n" });
+        }
+        content
+    }
+
+    #[cfg(feature = "gui")]
+    /// Marks as solid, in the map file it was read from, the fluid nodes
+    /// that are solid in this map. Every other line (header, coordinates,
+    /// untouched nodes) is kept as is, and the original file is first copied
+    /// to a timestamped `<name>_backup_<date>_<time>` file next to it, whose
+    /// path is returned.
+    pub(crate) fn write_solid_nodes_to_file(&self, path: &Path) -> Result<PathBuf, String> {
+        let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let mut new_content = String::with_capacity(content.len());
+        let mut n_read = 0;
+        for line in content.lines() {
+            match self.parse_file_line(line, n_read)? {
+                Some((index, 0)) if self.solid[index] => {
+                    let (coordinates, _) = line.rsplit_once(',').unwrap_or(("", line));
+                    if line.contains(',') {
+                        new_content.push_str(coordinates);
+                        new_content.push(',');
+                    }
+                    new_content.push('1');
+                    n_read += 1;
+                }
+                Some(_) => {
+                    new_content.push_str(line);
+                    n_read += 1;
+                }
+                None => new_content.push_str(line),
+            }
+            new_content.push('\n');
+        }
+        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+        let file_stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let backup_name = match path.extension() {
+            Some(extension) => format!(
+                "{}_backup_{}.{}",
+                file_stem,
+                timestamp,
+                extension.to_string_lossy()
+            ),
+            None => format!("{}_backup_{}", file_stem, timestamp),
+        };
+        let backup_path = path.with_file_name(backup_name);
+        std::fs::copy(path, &backup_path).map_err(|e| e.to_string())?;
+        std::fs::write(path, new_content).map_err(|e| e.to_string())?;
+        Ok(backup_path)
+    }
+}
+
+#[cfg(feature = "gui")]
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub(crate) enum AxisConnectionKind {
+    InflowOutflow,
+    Periodic,
+}
+
+#[cfg(feature = "gui")]
+pub(crate) struct AxisConnection {
+    pub(crate) axis: usize,
+    pub(crate) kind: AxisConnectionKind,
+    pub(crate) connected: bool,
+}
+
+#[cfg(feature = "gui")]
+pub(crate) struct PercolationReport {
+    pub(crate) n_fluid_nodes: usize,
+    pub(crate) component_sizes: Vec<usize>,
+    pub(crate) isolated_components: Vec<usize>,
+    pub(crate) n_isolated_nodes: usize,
+    pub(crate) axis_connections: Vec<AxisConnection>,
+    labels: Vec<Option<usize>>,
+}
+
+#[cfg(feature = "gui")]
+struct Labeling {
+    labels: Vec<Option<usize>>,
+    sizes: Vec<usize>,
+    touched_faces: Vec<[bool; 6]>,
+}
+
+#[cfg(feature = "gui")]
+fn get_face_slot(axis: usize, is_min_side: bool) -> usize {
+    2 * axis + if is_min_side { 0 } else { 1 }
+}
+
+#[cfg(feature = "gui")]
+fn label_components(map: &NodeTypeMap, velocities: &[[isize; 3]], wrap: [bool; 3]) -> Labeling {
+    let grid = map.grid;
+    let mut labels = vec![None; map.solid.len()];
+    let mut sizes = vec![];
+    let mut touched_faces = vec![];
+    let mut stack = vec![];
+    for start in 0..map.solid.len() {
+        if map.solid[start] || labels[start].is_some() {
+            continue;
+        }
+        let label = sizes.len();
+        let mut size = 0;
+        let mut faces = [false; 6];
+        labels[start] = Some(label);
+        stack.push(start);
+        while let Some(index) = stack.pop() {
+            size += 1;
+            let coords = map.get_coords(index);
+            for axis in 0..3 {
+                if grid[axis] > 1 {
+                    faces[get_face_slot(axis, true)] |= coords[axis] == 0;
+                    faces[get_face_slot(axis, false)] |= coords[axis] == grid[axis] - 1;
+                }
+            }
+            'velocities: for c in velocities {
+                let mut neighbour = [0_usize; 3];
+                for axis in 0..3 {
+                    let n = grid[axis] as isize;
+                    let mut coord = coords[axis] as isize + c[axis];
+                    if coord < 0 || coord >= n {
+                        if !wrap[axis] {
+                            continue 'velocities;
+                        }
+                        coord = coord.rem_euclid(n);
+                    }
+                    neighbour[axis] = coord as usize;
+                }
+                let neighbour_index = map.get_index(neighbour[0], neighbour[1], neighbour[2]);
+                if !map.solid[neighbour_index] && labels[neighbour_index].is_none() {
+                    labels[neighbour_index] = Some(label);
+                    stack.push(neighbour_index);
+                }
+            }
+        }
+        sizes.push(size);
+        touched_faces.push(faces);
+    }
+    Labeling {
+        labels,
+        sizes,
+        touched_faces,
+    }
+}
+
+#[cfg(feature = "gui")]
+/// A face lets the flow through when it prescribes a pressure or a velocity
+/// across it; a bounce-back face with no normal velocity (a wall, possibly
+/// moving along itself) is closed.
+fn is_open(boundary_face: BoundaryFaceGui, boundary_condition: &BoundaryConditionGui) -> bool {
+    match boundary_condition {
+        BoundaryConditionGui::BounceBack { ux, uy, uz, .. } => {
+            [*ux, *uy, *uz][boundary_face.get_axis()] != 0.0
+        }
+        BoundaryConditionGui::AntiBounceBack { .. } => true,
+        BoundaryConditionGui::NoSlip | BoundaryConditionGui::Periodic => false,
+    }
+}
+
+#[cfg(feature = "gui")]
+/// Labels the fluid nodes connected through the velocity set stencil and
+/// checks whether the fluid spans the inflow/outflow and periodic directions.
+/// Components that touch no inflow/outflow face are reported as isolated; when
+/// the domain has no such face, every component except the largest is.
+pub(crate) fn analyze_percolation(
+    map: &NodeTypeMap,
+    dim: Dimensionality,
+    velocity_set: &VelocitySetGui,
+    boundary_conditions: &[momentum::FaceBC],
+) -> PercolationReport {
+    let velocities = velocity_set
+        .get_velocities()
+        .into_iter()
+        .filter(|c| *c != [0, 0, 0])
+        .collect::<Vec<[isize; 3]>>();
+    let n_axes = match dim {
+        Dimensionality::D2 => 2,
+        Dimensionality::D3 => 3,
+    };
+    let mut open_faces = [false; 6];
+    let mut wrap = [false; 3];
+    for face_bc in boundary_conditions {
+        let face = face_bc.boundary_face;
+        if face.get_axis() >= n_axes {
+            continue;
+        }
+        if is_open(face, &face_bc.boundary_condition) {
+            open_faces[get_face_slot(face.get_axis(), face.is_min_side())] = true;
+        }
+        if face_bc.boundary_condition == BoundaryConditionGui::Periodic {
+            wrap[face.get_axis()] = true;
+        }
+    }
+
+    let labeling = label_components(map, &velocities, wrap);
+    let n_fluid_nodes = labeling.sizes.iter().sum();
+    let has_open_faces = open_faces.iter().any(|open| *open);
+    let largest_component = (0..labeling.sizes.len()).max_by_key(|i| labeling.sizes[*i]);
+    let isolated_components = (0..labeling.sizes.len())
+        .filter(|i| {
+            if has_open_faces {
+                !(0..6).any(|slot| open_faces[slot] && labeling.touched_faces[*i][slot])
+            } else {
+                Some(*i) != largest_component
+            }
+        })
+        .collect::<Vec<usize>>();
+    let n_isolated_nodes = isolated_components.iter().map(|i| labeling.sizes[*i]).sum();
+
+    let mut axis_connections = vec![];
+    for axis in 0..n_axes {
+        let (min_slot, max_slot) = (get_face_slot(axis, true), get_face_slot(axis, false));
+        let (kind, touched_faces) = if wrap[axis] {
+            let mut unwrapped = wrap;
+            unwrapped[axis] = false;
+            let unwrapped_labeling = label_components(map, &velocities, unwrapped);
+            (
+                AxisConnectionKind::Periodic,
+                unwrapped_labeling.touched_faces,
+            )
+        } else if open_faces[min_slot] && open_faces[max_slot] {
+            (
+                AxisConnectionKind::InflowOutflow,
+                labeling.touched_faces.clone(),
+            )
+        } else {
+            continue;
+        };
+        axis_connections.push(AxisConnection {
+            axis,
+            kind,
+            connected: touched_faces
+                .iter()
+                .any(|faces| faces[min_slot] && faces[max_slot]),
+        });
+    }
+
+    PercolationReport {
+        n_fluid_nodes,
+        component_sizes: labeling.sizes,
+        isolated_components,
+        n_isolated_nodes,
+        axis_connections,
+        labels: labeling.labels,
+    }
+}
+
+#[cfg(feature = "gui")]
+impl PercolationReport {
+    pub(crate) fn fill_isolated_components(&self, map: &mut NodeTypeMap) {
+        for (index, label) in self.labels.iter().enumerate() {
+            if let Some(label) = label
+                && self.isolated_components.contains(label)
+            {
+                map.solid[index] = true;
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "gui"))]
+mod tests {
+    use super::*;
+
+    /// Builds a 2D map from rows of `#` (solid) and `.` (fluid), the first
+    /// row being y = 0.
+    fn get_map(rows: &[&str]) -> NodeTypeMap {
+        let grid = [rows[0].len(), rows.len(), 1];
+        let mut map = NodeTypeMap::new_fluid(grid);
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                let index = map.get_index(x, y, 0);
+                map.solid[index] = c == '#';
+            }
+        }
+        map
+    }
+
+    fn get_boundary_conditions(
+        [west, east, south, north]: [BoundaryConditionGui; 4],
+    ) -> Vec<momentum::FaceBC> {
+        [
+            (BoundaryFaceGui::West, west),
+            (BoundaryFaceGui::East, east),
+            (BoundaryFaceGui::South, south),
+            (BoundaryFaceGui::North, north),
+        ]
+        .into_iter()
+        .map(|(boundary_face, boundary_condition)| momentum::FaceBC {
+            boundary_face,
+            boundary_condition,
+        })
+        .collect()
+    }
+
+    fn analyze(
+        map: &NodeTypeMap,
+        boundary_conditions: [BoundaryConditionGui; 4],
+    ) -> PercolationReport {
+        analyze_percolation(
+            map,
+            Dimensionality::D2,
+            &VelocitySetGui::D2Q9,
+            &get_boundary_conditions(boundary_conditions),
+        )
+    }
+
+    const WALL: BoundaryConditionGui = BoundaryConditionGui::NoSlip;
+
+    fn pressure(rho: f64) -> BoundaryConditionGui {
+        BoundaryConditionGui::AntiBounceBack { rho }
+    }
+
+    fn velocity(ux: f64, uy: f64) -> BoundaryConditionGui {
+        BoundaryConditionGui::BounceBack {
+            rho: 1.0,
+            ux,
+            uy,
+            uz: 0.0,
+        }
+    }
+
+    #[test]
+    fn pocket_is_isolated_from_a_cluster_touching_several_faces() {
+        let mut map = get_map(&[
+            "......", //
+            "......", //
+            "######", //
+            "#..#..", //
+            "####..", //
+        ]);
+        let report = analyze(&map, [pressure(1.001), pressure(1.0), WALL, WALL]);
+        assert_eq!(report.n_fluid_nodes, 18);
+        assert_eq!(report.component_sizes.len(), 3);
+        assert_eq!(report.isolated_components.len(), 1);
+        assert_eq!(report.n_isolated_nodes, 2);
+        assert_eq!(report.axis_connections.len(), 1);
+        assert_eq!(report.axis_connections[0].axis, 0);
+        assert!(report.axis_connections[0].kind == AxisConnectionKind::InflowOutflow);
+        assert!(report.axis_connections[0].connected);
+
+        report.fill_isolated_components(&mut map);
+        assert!(map.is_solid(1, 3, 0) && map.is_solid(2, 3, 0));
+        assert!(!map.is_solid(4, 3, 0));
+    }
+
+    #[test]
+    fn diagonal_neighbours_connect_through_the_stencil() {
+        let map = get_map(&[
+            ".###", //
+            "#.##", //
+            "##..", //
+        ]);
+        let report = analyze(&map, [pressure(1.001), pressure(1.0), WALL, WALL]);
+        assert_eq!(report.component_sizes, vec![4]);
+        assert!(report.isolated_components.is_empty());
+        assert!(report.axis_connections[0].connected);
+    }
+
+    #[test]
+    fn blocked_channel_is_not_connected() {
+        let map = get_map(&[
+            "..#..", //
+            "..#..", //
+        ]);
+        let report = analyze(&map, [velocity(0.05, 0.0), pressure(1.0), WALL, WALL]);
+        assert_eq!(report.component_sizes.len(), 2);
+        assert!(report.isolated_components.is_empty());
+        assert!(!report.axis_connections[0].connected);
+    }
+
+    #[test]
+    fn walls_with_tangential_velocity_are_closed() {
+        let map = get_map(&[
+            "....", //
+            "####", //
+            "..#.", //
+        ]);
+        let report = analyze(&map, [WALL, WALL, WALL, velocity(0.1, 0.0)]);
+        assert!(report.axis_connections.is_empty());
+        assert_eq!(report.isolated_components.len(), 2);
+        assert_eq!(report.n_isolated_nodes, 3);
+    }
+
+    #[test]
+    fn periodic_axis_connects_through_the_unwrapped_domain() {
+        let map = get_map(&[
+            "....", //
+            "#.##", //
+            "....", //
+        ]);
+        let periodic = BoundaryConditionGui::Periodic;
+        let report = analyze(
+            &map,
+            [
+                periodic.clone(),
+                periodic.clone(),
+                periodic.clone(),
+                periodic,
+            ],
+        );
+        assert_eq!(report.axis_connections.len(), 2);
+        assert!(report.axis_connections[0].kind == AxisConnectionKind::Periodic);
+        assert!(report.axis_connections.iter().all(|c| c.connected));
+        assert!(report.isolated_components.is_empty());
+    }
+
+    #[test]
+    fn filling_keeps_the_map_file_format() {
+        let dir = std::env::temp_dir().join(format!("geometry_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("map.csv");
+        std::fs::write(&path, "x,y,type\n0,0,0\n1,0,0\n0,1,1\n1,1,0\n").unwrap();
+        let mut map = NodeTypeMap::from_file(&path, [2, 2, 1]).unwrap();
+        assert!(map.is_solid(0, 1, 0));
+        map.solid[1] = true;
+        let backup_path = map.write_solid_nodes_to_file(&path).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "x,y,type\n0,0,0\n1,0,1\n0,1,1\n1,1,0\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(&backup_path).unwrap(),
+            "x,y,type\n0,0,0\n1,0,0\n0,1,1\n1,1,0\n"
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
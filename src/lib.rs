@@ -1,2 +1,24 @@
+pub mod case;
+#[cfg(feature = "gui")]
+pub(crate) mod cases;
+pub mod cli;
+pub(crate) mod compare;
 pub(crate) mod config;
+#[cfg(feature = "gui")]
+pub(crate) mod dependency;
+#[cfg(feature = "gui")]
+pub(crate) mod estimate;
+pub(crate) mod expression;
+pub(crate) mod generator;
+pub(crate) mod geometry;
+pub(crate) mod metadata;
+#[cfg(feature = "gui")]
+pub(crate) mod naming;
+#[cfg(feature = "gui")]
+pub(crate) mod runner;
+#[cfg(feature = "gui")]
+pub(crate) mod settings;
+pub(crate) mod templates;
+#[cfg(feature = "gui")]
 pub mod ui;
+pub(crate) mod writer;
@@ -1,10 +1,37 @@
+use lbflow_case_builder::cli;
+#[cfg(feature = "gui")]
 use lbflow_case_builder::ui::GuiApp;
 
 fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    if let Some(result) = cli::run_command(&args) {
+        match result {
+            Ok(output) => print!("{}", output),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    run_gui();
+}
+
+#[cfg(feature = "gui")]
+fn run_gui() {
     let native_options = eframe::NativeOptions::default();
     let _ = eframe::run_native(
         "lbflow case builder",
         native_options,
-        Box::new(|_cc| Ok(Box::new(GuiApp::new()))),
+        Box::new(|cc| Ok(Box::new(GuiApp::new(cc)))),
     );
 }
+
+#[cfg(not(feature = "gui"))]
+fn run_gui() {
+    eprintln!("Built without the \"gui\" feature, only commands are available");
+    if let Some(Ok(usage)) = cli::run_command(&["help".to_string()]) {
+        eprint!("{}", usage);
+    }
+    std::process::exit(1);
+}
@@ -0,0 +1,591 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::*;
+use crate::templates::CaseTemplate;
+
+pub(crate) const CASE_MD_FILE_PATH: &str = "case.md";
+pub(crate) const CASE_JSON_FILE_PATH: &str = "case.json";
+const BUILDER_VERSION: &str = env!("CARGO_PKG_VERSION");
+pub(crate) const LATTICE_SOUND_SPEED: f64 = 0.577_350_269_189_625_8;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct ScalarDerivedQuantities {
+    pub(crate) name: String,
+    pub(crate) lattice_diffusivity: Option<f64>,
+    pub(crate) physical_diffusivity: Option<f64>,
+    pub(crate) peclet_number: Option<f64>,
+}
+
+/// Dimensionless numbers use the largest prescribed velocity and the
+/// smallest extent of the domain as reference scales.
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct DerivedQuantities {
+    pub(crate) lattice_viscosity: Option<f64>,
+    pub(crate) physical_viscosity: Option<f64>,
+    pub(crate) lattice_velocity_scale: f64,
+    pub(crate) physical_velocity_scale: f64,
+    pub(crate) lattice_length_scale: f64,
+    pub(crate) physical_length_scale: f64,
+    pub(crate) reynolds_number: Option<f64>,
+    pub(crate) mach_number: f64,
+    pub(crate) scalars: Vec<ScalarDerivedQuantities>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct CaseMetadata {
+    pub(crate) builder_version: String,
+    pub(crate) generated_at: String,
+    pub(crate) case: CaseTemplate,
+    pub(crate) derived: DerivedQuantities,
+}
+
+const GENERATED_ON_PREFIX: &str = "Generated on ";
+
+/// Whether an existing metadata file only differs from the generated one by
+/// its generation timestamp. Always false for other files.
+pub(crate) fn is_same_apart_from_timestamp(
+    file_path: &str,
+    existing: &str,
+    generated: &str,
+) -> bool {
+    match file_path {
+        CASE_MD_FILE_PATH => {
+            let is_kept = |line: &&str| !line.starts_with(GENERATED_ON_PREFIX);
+            existing
+                .lines()
+                .filter(is_kept)
+                .eq(generated.lines().filter(is_kept))
+        }
+        CASE_JSON_FILE_PATH => {
+            let without_timestamp = |content: &str| {
+                let mut value = serde_json::from_str::<serde_json::Value>(content).ok()?;
+                value.as_object_mut()?.remove("generated_at");
+                Some(value)
+            };
+            let existing = without_timestamp(existing);
+            existing.is_some() && existing == without_timestamp(generated)
+        }
+        _ => false,
+    }
+}
+
+/// Kinematic viscosity or diffusivity in lattice units, unknown for MRT.
+pub(crate) fn get_lattice_transport_coefficient(
+    collision_operator: &CollisionOperatorGui,
+) -> Option<f64> {
+    let tau = match collision_operator {
+        CollisionOperatorGui::BGK { tau } => *tau,
+        CollisionOperatorGui::TRT { omega_plus, .. } => 1.0 / omega_plus,
+        CollisionOperatorGui::MRT => return None,
+    };
+    Some(LATTICE_SOUND_SPEED * LATTICE_SOUND_SPEED * (tau - 0.5))
+}
+
+fn get_lattice_velocity_scale(m_cfg: &momentum::GuiConfig) -> f64 {
+    let mut velocities = vec![];
+    if let momentum::InitialVelocityGui::Uniform { ux, uy, uz } = &m_cfg.initial_velocity {
+        velocities.push([*ux, *uy, *uz]);
+    }
+    let boundary_conditions = m_cfg
+        .boundary_conditions
+        .iter()
+        .map(|face_bc| &face_bc.boundary_condition)
+        .chain(
+            m_cfg
+                .boundary_patches
+                .iter()
+                .map(|patch_bc| &patch_bc.boundary_condition),
+        );
+    for boundary_condition in boundary_conditions {
+        if let momentum::BoundaryConditionGui::BounceBack { ux, uy, uz, .. } = boundary_condition {
+            velocities.push([*ux, *uy, *uz]);
+        }
+    }
+    velocities
+        .iter()
+        .map(|u| (u[0] * u[0] + u[1] * u[1] + u[2] * u[2]).sqrt())
+        .fold(0.0, f64::max)
+}
+
+fn get_lattice_length_scale(d_cfg: &domain::GuiConfig) -> f64 {
+    let n_axes = match d_cfg.dim {
+        Dimensionality::D2 => 2,
+        Dimensionality::D3 => 3,
+    };
+    d_cfg.grid[..n_axes].iter().copied().min().unwrap_or(0) as f64
+}
+
+pub(crate) fn get_derived_quantities(case: &CaseTemplate) -> DerivedQuantities {
+    let m_cfg = &case.m_cfg;
+    let velocity_conversion = m_cfg.delta_x / m_cfg.delta_t;
+    let diffusivity_conversion = m_cfg.delta_x * m_cfg.delta_x / m_cfg.delta_t;
+    let lattice_viscosity = get_lattice_transport_coefficient(&m_cfg.collision_operator);
+    let lattice_velocity_scale = get_lattice_velocity_scale(m_cfg);
+    let lattice_length_scale = get_lattice_length_scale(&case.d_cfg);
+    let scalars = case
+        .s_cfg_vec
+        .iter()
+        .map(|s_cfg| {
+            let lattice_diffusivity = get_lattice_transport_coefficient(&s_cfg.collision_operator);
+            ScalarDerivedQuantities {
+                name: s_cfg.name.clone(),
+                lattice_diffusivity,
+                physical_diffusivity: lattice_diffusivity.map(|d| d * diffusivity_conversion),
+                peclet_number: lattice_diffusivity
+                    .map(|d| lattice_velocity_scale * lattice_length_scale / d),
+            }
+        })
+        .collect();
+    DerivedQuantities {
+        lattice_viscosity,
+        physical_viscosity: lattice_viscosity.map(|nu| nu * diffusivity_conversion),
+        lattice_velocity_scale,
+        physical_velocity_scale: lattice_velocity_scale * velocity_conversion,
+        lattice_length_scale,
+        physical_length_scale: lattice_length_scale * m_cfg.delta_x,
+        reynolds_number: lattice_viscosity
+            .map(|nu| lattice_velocity_scale * lattice_length_scale / nu),
+        mach_number: lattice_velocity_scale / LATTICE_SOUND_SPEED,
+        scalars,
+    }
+}
+
+fn get_optional_literal(value: Option<f64>) -> String {
+    match value {
+        Some(value) => format!("{:.6e}", value),
+        None => "unknown (MRT)".to_string(),
+    }
+}
+
+fn get_momentum_bc_description(
+    boundary_condition: &momentum::BoundaryConditionGui,
+    m_cfg: &momentum::GuiConfig,
+) -> String {
+    let velocity_conversion = m_cfg.delta_x / m_cfg.delta_t;
+    match boundary_condition {
+        momentum::BoundaryConditionGui::BounceBack { rho, ux, uy, uz } => format!(
+            "{} (rho = {}, u = ({}, {}, {}) lu, ({:.6e}, {:.6e}, {:.6e}) m/s)",
+            boundary_condition.get_label(),
+            rho,
+            ux,
+            uy,
+            uz,
+            ux * velocity_conversion,
+            uy * velocity_conversion,
+            uz * velocity_conversion
+        ),
+        momentum::BoundaryConditionGui::AntiBounceBack { rho } => format!(
+            "{} (rho = {} lu, {:.6e} kg/m^3)",
+            boundary_condition.get_label(),
+            rho,
+            rho * m_cfg.physical_density
+        ),
+        _ => boundary_condition.get_label().to_string(),
+    }
+}
+
+fn get_patch_description(patch: &FacePatchGui, dim: Dimensionality) -> String {
+    let ranges = get_tangential_axes(patch.boundary_face)
+        .iter()
+        .zip(&patch.ranges)
+        .filter(|(axis, _)| **axis < dim.get_n_axes())
+        .map(|(axis, [start, end])| format!("{} {}..{}", AXIS_NAMES[*axis], start, end))
+        .collect::<Vec<String>>();
+    format!(
+        "{}, {}",
+        patch.boundary_face.to_literal(),
+        ranges.join(", ")
+    )
+}
+
+fn get_scalar_bc_description(boundary_condition: &scalar::BoundaryConditionGui) -> String {
+    match boundary_condition {
+        scalar::BoundaryConditionGui::AntiBounceBack { value } => {
+            format!("{} (value = {})", boundary_condition.get_label(), value)
+        }
+        _ => boundary_condition.get_label().to_string(),
+    }
+}
+
+fn get_coordinates_literal(coordinates: CoordinatesGui) -> &'static str {
+    match coordinates {
+        CoordinatesGui::Lattice => "lattice coordinates",
+        CoordinatesGui::Physical => "physical coordinates",
+    }
+}
+
+fn get_node_type_mask_description(d_cfg: &domain::GuiConfig) -> String {
+    match &d_cfg.node_type_mask {
+        NodeTypeMaskGui::OnlyFluidNodes => "Only fluid nodes".to_string(),
+        NodeTypeMaskGui::FromMapFile => format!("From map file `{}`", domain::MAP_FILE_PATH),
+        NodeTypeMaskGui::Expression { solid, coordinates } => format!(
+            "Solid where `{}` > 0 ({})",
+            solid,
+            get_coordinates_literal(*coordinates)
+        ),
+    }
+}
+
+/// Lattice and physical descriptions of the initial density.
+fn get_initial_density_descriptions(m_cfg: &momentum::GuiConfig) -> (String, String) {
+    match &m_cfg.initial_density {
+        momentum::InitialDensityGui::Uniform { rho } => (
+            format!("Uniform {}", rho),
+            format!("Uniform {:.6e} kg/m^3", rho * m_cfg.physical_density),
+        ),
+        momentum::InitialDensityGui::FromTimeStep { time_step } => {
+            (format!("From time step {}", time_step), String::new())
+        }
+        momentum::InitialDensityGui::FromFile { file_path } => {
+            (format!("From file `{}`", file_path), String::new())
+        }
+        momentum::InitialDensityGui::Expression { rho, coordinates } => (
+            format!("`{}` ({})", rho, get_coordinates_literal(*coordinates)),
+            format!("`{}` x {} kg/m^3", rho, m_cfg.physical_density),
+        ),
+    }
+}
+
+/// Lattice and physical descriptions of the initial velocity.
+fn get_initial_velocity_descriptions(m_cfg: &momentum::GuiConfig) -> (String, String) {
+    let velocity_conversion = m_cfg.delta_x / m_cfg.delta_t;
+    match &m_cfg.initial_velocity {
+        momentum::InitialVelocityGui::Uniform { ux, uy, uz } => (
+            format!("Uniform ({}, {}, {})", ux, uy, uz),
+            format!(
+                "Uniform ({:.6e}, {:.6e}, {:.6e}) m/s",
+                ux * velocity_conversion,
+                uy * velocity_conversion,
+                uz * velocity_conversion
+            ),
+        ),
+        momentum::InitialVelocityGui::FromTimeStep { time_step } => {
+            (format!("From time step {}", time_step), String::new())
+        }
+        momentum::InitialVelocityGui::FromFile { file_path } => {
+            (format!("From file `{}`", file_path), String::new())
+        }
+        momentum::InitialVelocityGui::Expression {
+            ux,
+            uy,
+            uz,
+            coordinates,
+        } => (
+            format!(
+                "(`{}`, `{}`, `{}`) ({})",
+                ux,
+                uy,
+                uz,
+                get_coordinates_literal(*coordinates)
+            ),
+            format!(
+                "(`{}`, `{}`, `{}`) x {:.6e} m/s",
+                ux, uy, uz, velocity_conversion
+            ),
+        ),
+    }
+}
+
+fn get_initial_scalar_value_description(s_cfg: &scalar::GuiConfig) -> String {
+    match &s_cfg.initial_scalar_value {
+        scalar::InitialScalarValueGui::Uniform { value } => format!("Uniform {}", value),
+        scalar::InitialScalarValueGui::FromTimeStep { time_step } => {
+            format!("From time step {}", time_step)
+        }
+        scalar::InitialScalarValueGui::FromFile { file_path } => {
+            format!("From file `{}`", file_path)
+        }
+        scalar::InitialScalarValueGui::Expression { value, coordinates } => {
+            format!("`{}` ({})", value, get_coordinates_literal(*coordinates))
+        }
+    }
+}
+
+fn get_inner_boundary_condition_label(
+    inner_boundary_condition: &scalar::InnerBoundaryConditionGui,
+) -> &'static str {
+    match inner_boundary_condition {
+        scalar::InnerBoundaryConditionGui::InnerBounceBack => "Bounce-back",
+        scalar::InnerBoundaryConditionGui::InnerAntiBounceBack => "Anti-bounce-back",
+    }
+}
+
+fn get_dependency_source_description(c_cfg: &CargoGuiConfig) -> String {
+    match &c_cfg.dependency_source {
+        DependencySourceGui::Path => format!("Path `{}`", c_cfg.source_code_path),
+        DependencySourceGui::Git {
+            url,
+            reference,
+            value,
+        } => {
+            let reference_literal = match reference {
+                GitReferenceGui::Rev => "rev",
+                GitReferenceGui::Tag => "tag",
+            };
+            format!("Git `{}`, {} `{}`", url, reference_literal, value)
+        }
+        DependencySourceGui::Registry { version } => format!("Registry, version {}", version),
+    }
+}
+
+impl CaseMetadata {
+    pub(crate) fn new(case: CaseTemplate) -> Self {
+        CaseMetadata {
+            builder_version: BUILDER_VERSION.to_string(),
+            generated_at: chrono::Local::now()
+                .format("%Y-%m-%d %H:%M:%S %z")
+                .to_string(),
+            derived: get_derived_quantities(&case),
+            case,
+        }
+    }
+
+    pub(crate) fn get_json_content(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| e.to_string())
+    }
+
+    /// Describes the whole case, in lattice and physical units where the
+    /// quantity has both.
+    pub(crate) fn get_markdown_content(&self) -> String {
+        let case = &self.case;
+        let d_cfg = &case.d_cfg;
+        let m_cfg = &case.m_cfg;
+        let c_cfg = &case.c_cfg;
+        let derived = &self.derived;
+        let dim = d_cfg.dim;
+        let n_axes = dim.get_n_axes();
+        let velocity_conversion = m_cfg.delta_x / m_cfg.delta_t;
+        let diffusivity_conversion = m_cfg.delta_x * m_cfg.delta_x / m_cfg.delta_t;
+        let dim_literal = match dim {
+            Dimensionality::D2 => "2D",
+            Dimensionality::D3 => "3D",
+        };
+        let grid_literal = d_cfg.grid[..n_axes]
+            .iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<String>>()
+            .join(" x ");
+        let extent_literal = d_cfg.grid[..n_axes]
+            .iter()
+            .map(|n| format!("{:.6e}", *n as f64 * m_cfg.delta_x))
+            .collect::<Vec<String>>()
+            .join(" x ");
+        let mut lines = vec![
+            format!("# {}", c_cfg.case_name),
+            String::new(),
+            format!(
+                "{}{} by lbflow_case_builder {}.",
+                GENERATED_ON_PREFIX, self.generated_at, self.builder_version
+            ),
+            String::new(),
+            "## Domain".to_string(),
+            String::new(),
+            "| Parameter | Lattice units | Physical units |".to_string(),
+            "| --- | --- | --- |".to_string(),
+            format!("| Dimensions | {} | |", dim_literal),
+            format!("| Grid | {} nodes | {} m |", grid_literal, extent_literal),
+        ];
+        let mut node_type_mask_description = get_node_type_mask_description(d_cfg);
+        let has_patches = !m_cfg.boundary_patches.is_empty()
+            || case
+                .s_cfg_vec
+                .iter()
+                .any(|s_cfg| !s_cfg.boundary_patches.is_empty());
+        if has_patches {
+            node_type_mask_description.push_str(&format!(
+                ", with the patched faces solid outside their patches, in `{}`",
+                domain::MAP_FILE_PATH
+            ));
+        }
+        lines.push(format!(
+            "| Node-type mask | {} | |",
+            node_type_mask_description
+        ));
+        let (initial_density_lattice, initial_density_physical) =
+            get_initial_density_descriptions(m_cfg);
+        let (initial_velocity_lattice, initial_velocity_physical) =
+            get_initial_velocity_descriptions(m_cfg);
+        lines.extend([
+            String::new(),
+            "## Momentum".to_string(),
+            String::new(),
+            "| Parameter | Lattice units | Physical units |".to_string(),
+            "| --- | --- | --- |".to_string(),
+            format!("| Velocity set | {} | |", m_cfg.velocity_set.get_label()),
+            format!(
+                "| Collision operator | {} | |",
+                m_cfg.collision_operator.get_label()
+            ),
+            format!("| Delta x | 1 | {} m |", m_cfg.delta_x),
+            format!("| Delta t | 1 | {} s |", m_cfg.delta_t),
+            format!("| Velocity | 1 | {:.6e} m/s |", velocity_conversion),
+            format!("| Density | 1 | {} kg/m^3 |", m_cfg.physical_density),
+            format!("| Reference pressure | | {} Pa |", m_cfg.reference_pressure),
+            format!(
+                "| Initial density | {} | {} |",
+                initial_density_lattice, initial_density_physical
+            ),
+            format!(
+                "| Initial velocity | {} | {} |",
+                initial_velocity_lattice, initial_velocity_physical
+            ),
+        ]);
+        for s_cfg in &case.s_cfg_vec {
+            let lattice_diffusivity = get_lattice_transport_coefficient(&s_cfg.collision_operator);
+            lines.extend([
+                String::new(),
+                format!("## Scalar {}", s_cfg.name),
+                String::new(),
+                "| Parameter | Lattice units | Physical units |".to_string(),
+                "| --- | --- | --- |".to_string(),
+                format!("| Velocity set | {} | |", s_cfg.velocity_set.get_label()),
+                format!(
+                    "| Collision operator | {} | |",
+                    s_cfg.collision_operator.get_label()
+                ),
+                format!(
+                    "| Diffusivity | {} | {} m^2/s |",
+                    get_optional_literal(lattice_diffusivity),
+                    get_optional_literal(lattice_diffusivity.map(|d| d * diffusivity_conversion))
+                ),
+                format!(
+                    "| Initial value | {} | |",
+                    get_initial_scalar_value_description(s_cfg)
+                ),
+                format!(
+                    "| Inner boundary condition | {} | |",
+                    get_inner_boundary_condition_label(&s_cfg.inner_boundary_condition)
+                ),
+            ]);
+        }
+        lines.extend([
+            String::new(),
+            "## Derived numbers".to_string(),
+            String::new(),
+            "Reference velocity: largest prescribed velocity. Reference length: smallest domain extent."
+                .to_string(),
+            String::new(),
+            "| Quantity | Lattice units | Physical units |".to_string(),
+            "| --- | --- | --- |".to_string(),
+            format!(
+                "| Kinematic viscosity | {} | {} m^2/s |",
+                get_optional_literal(derived.lattice_viscosity),
+                get_optional_literal(derived.physical_viscosity)
+            ),
+            format!(
+                "| Reference velocity | {:.6e} | {:.6e} m/s |",
+                derived.lattice_velocity_scale, derived.physical_velocity_scale
+            ),
+            format!(
+                "| Reference length | {} | {:.6e} m |",
+                derived.lattice_length_scale, derived.physical_length_scale
+            ),
+            format!(
+                "| Reynolds number | {} | |",
+                get_optional_literal(derived.reynolds_number)
+            ),
+            format!("| Mach number | {:.6e} | |", derived.mach_number),
+        ]);
+        for scalar in &derived.scalars {
+            lines.push(format!(
+                "| Peclet number of {} | {} | |",
+                scalar.name,
+                get_optional_literal(scalar.peclet_number)
+            ));
+        }
+        lines.extend([
+            String::new(),
+            "## Boundary conditions".to_string(),
+            String::new(),
+        ]);
+        let mut header = "| Face | Momentum |".to_string();
+        let mut separator = "| --- | --- |".to_string();
+        for s_cfg in &case.s_cfg_vec {
+            header.push_str(&format!(" {} |", s_cfg.name));
+            separator.push_str(" --- |");
+        }
+        lines.push(header);
+        lines.push(separator);
+        for boundary_face in BoundaryFaceGui::get_faces(dim) {
+            let mut row = format!("| {} |", boundary_face.to_literal());
+            let m_description = match m_cfg.get_face_bc(boundary_face) {
+                Some(face_bc) => get_momentum_bc_description(&face_bc.boundary_condition, m_cfg),
+                None => "-".to_string(),
+            };
+            row.push_str(&format!(" {} |", m_description));
+            for s_cfg in &case.s_cfg_vec {
+                let s_description = match s_cfg.get_face_bc(boundary_face) {
+                    Some(face_bc) => get_scalar_bc_description(&face_bc.boundary_condition),
+                    None => "-".to_string(),
+                };
+                row.push_str(&format!(" {} |", s_description));
+            }
+            lines.push(row);
+        }
+        let mut patch_lines = vec![];
+        for patch_bc in &m_cfg.boundary_patches {
+            patch_lines.push(format!(
+                "- {}: momentum {}",
+                get_patch_description(&patch_bc.patch, dim),
+                get_momentum_bc_description(&patch_bc.boundary_condition, m_cfg)
+            ));
+        }
+        for s_cfg in &case.s_cfg_vec {
+            for patch_bc in &s_cfg.boundary_patches {
+                patch_lines.push(format!(
+                    "- {}: {} {}",
+                    get_patch_description(&patch_bc.patch, dim),
+                    s_cfg.name,
+                    get_scalar_bc_description(&patch_bc.boundary_condition)
+                ));
+            }
+        }
+        if !patch_lines.is_empty() {
+            lines.extend([
+                String::new(),
+                "Patches (the rest of a patched face is solid):".to_string(),
+                String::new(),
+            ]);
+            lines.extend(patch_lines);
+        }
+        let release_profile = &c_cfg.release_profile;
+        let par_mode_literal = match c_cfg.par_mode {
+            ParModeGui::Safe => "Safe",
+            ParModeGui::Unsafe => "Unsafe fast path",
+        };
+        let codegen_units_literal = match release_profile.codegen_units {
+            Some(codegen_units) => codegen_units.to_string(),
+            None => "default".to_string(),
+        };
+        lines.extend([
+            String::new(),
+            "## Cargo".to_string(),
+            String::new(),
+            "| Setting | Value |".to_string(),
+            "| --- | --- |".to_string(),
+            format!(
+                "| lbflow_soa source | {} |",
+                get_dependency_source_description(c_cfg)
+            ),
+            format!("| Parallel mode | {} |", par_mode_literal),
+            format!("| target-cpu=native | {} |", c_cfg.target_cpu_native),
+            format!("| Workspace member | {} |", c_cfg.workspace),
+            format!("| Release LTO | {} |", release_profile.lto.get_label()),
+            format!("| Release codegen units | {} |", codegen_units_literal),
+            format!(
+                "| Release panic = abort | {} |",
+                release_profile.panic_abort
+            ),
+            format!("| Release debug info | {} |", release_profile.debug),
+        ]);
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    pub(crate) fn get_files(&self) -> Result<Vec<(String, String)>, String> {
+        Ok(vec![
+            (CASE_MD_FILE_PATH.to_string(), self.get_markdown_content()),
+            (CASE_JSON_FILE_PATH.to_string(), self.get_json_content()?),
+        ])
+    }
+}
@@ -0,0 +1,243 @@
+use std::path::Path;
+
+pub(crate) const DEFAULT_CASE_NAME_PATTERN: &str = "case_{series:3}_{variant:2}";
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+enum Field {
+    Series,
+    Variant,
+}
+
+enum Segment {
+    Literal(String),
+    Number { field: Field, width: usize },
+}
+
+/// A case naming pattern such as `case_{series:3}_{variant:2}`, where the
+/// optional number after the colon is the zero-padded width.
+pub(crate) struct NamingScheme {
+    segments: Vec<Segment>,
+}
+
+impl NamingScheme {
+    pub(crate) fn parse(pattern: &str) -> Result<Self, String> {
+        let mut segments = vec![];
+        let mut rest = pattern;
+        while let Some(start) = rest.find('{') {
+            if start > 0 {
+                segments.push(Segment::Literal(rest[..start].to_string()));
+            }
+            let end = rest[start..]
+                .find('}')
+                .map(|end| start + end)
+                .ok_or("Unclosed '{' in the pattern")?;
+            let placeholder = &rest[start + 1..end];
+            let (name, width) = match placeholder.split_once(':') {
+                Some((name, width)) => (
+                    name,
+                    width
+                        .parse::<usize>()
+                        .map_err(|_| format!("Invalid width \"{}\"", width))?,
+                ),
+                None => (placeholder, 0),
+            };
+            let field = match name {
+                "series" => Field::Series,
+                "variant" => Field::Variant,
+                _ => return Err(format!("Unknown placeholder \"{{{}}}\"", name)),
+            };
+            if let Some(Segment::Number { .. }) = segments.last() {
+                return Err("Placeholders must be separated by text".to_string());
+            }
+            segments.push(Segment::Number { field, width });
+            rest = &rest[end + 1..];
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Literal(rest.to_string()));
+        }
+        // Numbers are read up to the first non-digit, so the text after a
+        // placeholder cannot start with one.
+        for pair in segments.windows(2) {
+            if let [Segment::Number { .. }, Segment::Literal(literal)] = pair
+                && literal.starts_with(|c: char| c.is_ascii_digit())
+            {
+                return Err("Placeholders must not be followed by a digit".to_string());
+            }
+        }
+        for field in [Field::Series, Field::Variant] {
+            let n_fields = segments
+                .iter()
+                .filter(
+                    |segment| matches!(segment, Segment::Number { field: f, .. } if *f == field),
+                )
+                .count();
+            if n_fields != 1 {
+                return Err("The pattern needs one {series} and one {variant}".to_string());
+            }
+        }
+        Ok(NamingScheme { segments })
+    }
+
+    pub(crate) fn format(&self, series: usize, variant: usize) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Literal(literal) => literal.clone(),
+                Segment::Number { field, width } => {
+                    let value = match field {
+                        Field::Series => series,
+                        Field::Variant => variant,
+                    };
+                    format!("{:0width$}", value, width = *width)
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the series and variant numbers of a name following the
+    /// pattern.
+    pub(crate) fn parse_name(&self, name: &str) -> Option<(usize, usize)> {
+        let mut rest = name;
+        let (mut series, mut variant) = (None, None);
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(literal) => rest = rest.strip_prefix(literal.as_str())?,
+                Segment::Number { field, .. } => {
+                    let n_digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+                    let value = rest[..n_digits].parse::<usize>().ok()?;
+                    rest = &rest[n_digits..];
+                    match field {
+                        Field::Series => series = Some(value),
+                        Field::Variant => variant = Some(value),
+                    }
+                }
+            }
+        }
+        if !rest.is_empty() {
+            return None;
+        }
+        Some((series?, variant?))
+    }
+
+    fn get_existing_numbers(&self, parent_dir: &Path) -> Vec<(usize, usize)> {
+        let Ok(entries) = std::fs::read_dir(parent_dir) else {
+            return vec![];
+        };
+        entries
+            .flatten()
+            .filter_map(|entry| self.parse_name(&entry.file_name().to_string_lossy()))
+            .collect()
+    }
+
+    /// First variant of the series after the last one in `parent_dir`.
+    pub(crate) fn get_next_series(&self, parent_dir: &Path) -> String {
+        let next_series = self
+            .get_existing_numbers(parent_dir)
+            .iter()
+            .map(|(series, _)| series + 1)
+            .max()
+            .unwrap_or(0);
+        self.format(next_series, 0)
+    }
+
+    /// Next free variant in the series of `case_name`, or in the last series
+    /// of `parent_dir` when the name does not follow the pattern.
+    pub(crate) fn get_next_variant(&self, parent_dir: &Path, case_name: &str) -> String {
+        let existing_numbers = self.get_existing_numbers(parent_dir);
+        let series = match self.parse_name(case_name) {
+            Some((series, _)) => series,
+            None => existing_numbers
+                .iter()
+                .map(|(series, _)| *series)
+                .max()
+                .unwrap_or(0),
+        };
+        let next_variant = existing_numbers
+            .iter()
+            .filter(|(s, _)| *s == series)
+            .map(|(_, variant)| variant + 1)
+            .max()
+            .unwrap_or(0);
+        self.format(series, next_variant)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DEFAULT_CASE_NAME_PATTERN, NamingScheme};
+
+    fn parse_error(pattern: &str) -> String {
+        NamingScheme::parse(pattern).err().unwrap()
+    }
+
+    #[test]
+    fn formatting_and_parsing_names() {
+        let scheme = NamingScheme::parse(DEFAULT_CASE_NAME_PATTERN).unwrap();
+        assert_eq!(scheme.format(7, 3), "case_007_03");
+        assert_eq!(scheme.format(1234, 100), "case_1234_100");
+        assert_eq!(scheme.parse_name("case_007_03"), Some((7, 3)));
+        assert_eq!(scheme.parse_name("case_1234_100"), Some((1234, 100)));
+        assert_eq!(scheme.parse_name("case_7_3"), Some((7, 3)));
+        assert_eq!(scheme.parse_name("case_007"), None);
+        assert_eq!(scheme.parse_name("case_007_03_old"), None);
+        assert_eq!(scheme.parse_name("case__03"), None);
+        assert_eq!(scheme.parse_name("other_007_03"), None);
+    }
+
+    #[test]
+    fn placeholders_in_any_order_and_without_width() {
+        let scheme = NamingScheme::parse("{variant}-of-{series:2}").unwrap();
+        assert_eq!(scheme.format(4, 12), "12-of-04");
+        assert_eq!(scheme.parse_name("12-of-04"), Some((4, 12)));
+        assert_eq!(scheme.parse_name("-of-04"), None);
+    }
+
+    #[test]
+    fn invalid_patterns() {
+        assert_eq!(
+            parse_error("case_{series}{variant}"),
+            "Placeholders must be separated by text"
+        );
+        assert_eq!(
+            parse_error("{series}_{variant}2"),
+            "Placeholders must not be followed by a digit"
+        );
+        assert_eq!(
+            parse_error("case_{series:x}_{variant}"),
+            "Invalid width \"x\""
+        );
+        assert_eq!(
+            parse_error("case_{series:}_{variant}"),
+            "Invalid width \"\""
+        );
+        assert_eq!(
+            parse_error("case_{series}_{variant"),
+            "Unclosed '{' in the pattern"
+        );
+        assert_eq!(
+            parse_error("case_{series}_{run}"),
+            "Unknown placeholder \"{run}\""
+        );
+        for pattern in ["case_{series}", "{series}_{variant}_{series}", "case"] {
+            assert_eq!(
+                parse_error(pattern),
+                "The pattern needs one {series} and one {variant}"
+            );
+        }
+    }
+
+    #[test]
+    fn next_series_and_variant() {
+        let dir = std::env::temp_dir().join(format!("naming_test_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        for name in ["case_000_00", "case_001_00", "case_001_04", "notes"] {
+            std::fs::create_dir_all(dir.join(name)).unwrap();
+        }
+        let scheme = NamingScheme::parse(DEFAULT_CASE_NAME_PATTERN).unwrap();
+        assert_eq!(scheme.get_next_series(&dir), "case_002_00");
+        assert_eq!(scheme.get_next_variant(&dir, "case_000_00"), "case_000_01");
+        assert_eq!(scheme.get_next_variant(&dir, "my_case"), "case_001_05");
+        assert_eq!(scheme.get_next_series(&dir.join("missing")), "case_000_00");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
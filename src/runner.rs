@@ -0,0 +1,252 @@
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+pub(crate) const RUN_LOG_FILE_NAME: &str = "last_run.log";
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub(crate) enum CargoCommand {
+    Build,
+    Run,
+}
+
+impl CargoCommand {
+    /// Run builds with JSON messages on stdout to find the case executable,
+    /// which is then started on its own so that its run can be timed apart
+    /// from the compilation.
+    fn get_args(self) -> &'static [&'static str] {
+        match self {
+            CargoCommand::Build => &["build", "--release"],
+            CargoCommand::Run => &[
+                "build",
+                "--release",
+                "--message-format=json-render-diagnostics",
+            ],
+        }
+    }
+
+    pub(crate) fn get_label(self) -> &'static str {
+        match self {
+            CargoCommand::Build => "cargo build --release",
+            CargoCommand::Run => "cargo build --release, then the case executable",
+        }
+    }
+}
+
+pub(crate) struct LogLine {
+    pub(crate) is_stderr: bool,
+    pub(crate) text: String,
+}
+
+/// A cargo process running in a case directory, followed for a run by the
+/// case executable, with their stdout and stderr collected line by line in
+/// the background.
+pub(crate) struct CargoProcess {
+    pub(crate) command: CargoCommand,
+    case_dir: PathBuf,
+    child: Child,
+    readers: Vec<JoinHandle<()>>,
+    pub(crate) log: Arc<Mutex<Vec<LogLine>>>,
+    executable: Arc<Mutex<Option<PathBuf>>>,
+    started: Instant,
+    run_started: Option<Instant>,
+    pub(crate) finished: Option<(Result<ExitStatus, String>, Duration)>,
+    run_elapsed: Option<Duration>,
+}
+
+/// Collects the lines of `stream` into `log`. With `executable`, the lines
+/// are cargo JSON messages: the path of the built executable is stored and
+/// only the lines that are not JSON are logged.
+fn spawn_reader(
+    stream: impl Read + Send + 'static,
+    is_stderr: bool,
+    log: Arc<Mutex<Vec<LogLine>>>,
+    executable: Option<Arc<Mutex<Option<PathBuf>>>>,
+) -> JoinHandle<()> {
+    std::thread::spawn(move || {
+        for text in BufReader::new(stream).lines().map_while(Result::ok) {
+            if let Some(executable) = &executable
+                && let Ok(message) = serde_json::from_str::<serde_json::Value>(&text)
+            {
+                if message["reason"] == "compiler-artifact"
+                    && let Some(path) = message["executable"].as_str()
+                    && let Ok(mut executable) = executable.lock()
+                {
+                    *executable = Some(PathBuf::from(path));
+                }
+                continue;
+            }
+            if let Ok(mut log) = log.lock() {
+                log.push(LogLine { is_stderr, text });
+            }
+        }
+    })
+}
+
+fn spawn_piped(
+    command: &mut Command,
+    log: &Arc<Mutex<Vec<LogLine>>>,
+    executable: Option<&Arc<Mutex<Option<PathBuf>>>>,
+) -> std::io::Result<(Child, Vec<JoinHandle<()>>)> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let mut readers = vec![];
+    if let Some(stdout) = child.stdout.take() {
+        readers.push(spawn_reader(
+            stdout,
+            false,
+            log.clone(),
+            executable.cloned(),
+        ));
+    }
+    if let Some(stderr) = child.stderr.take() {
+        readers.push(spawn_reader(stderr, true, log.clone(), None));
+    }
+    Ok((child, readers))
+}
+
+impl CargoProcess {
+    pub(crate) fn spawn(command: CargoCommand, case_dir: &Path) -> std::io::Result<Self> {
+        let log = Arc::new(Mutex::new(vec![]));
+        let executable = Arc::new(Mutex::new(None));
+        let (child, readers) = spawn_piped(
+            Command::new("cargo")
+                .args(command.get_args())
+                .current_dir(case_dir),
+            &log,
+            (command == CargoCommand::Run).then_some(&executable),
+        )?;
+        Ok(CargoProcess {
+            command,
+            case_dir: case_dir.to_path_buf(),
+            child,
+            readers,
+            log,
+            executable,
+            started: Instant::now(),
+            run_started: None,
+            finished: None,
+            run_elapsed: None,
+        })
+    }
+
+    pub(crate) fn get_elapsed(&self) -> Duration {
+        match &self.finished {
+            Some((_, elapsed)) => *elapsed,
+            None => self.started.elapsed(),
+        }
+    }
+
+    /// Duration of the case executable alone, once it has finished.
+    pub(crate) fn get_run_elapsed(&self) -> Option<Duration> {
+        self.run_elapsed
+    }
+
+    /// Starts the built case executable in the case directory, as `cargo run`
+    /// would.
+    fn spawn_executable(&mut self) -> Result<(), String> {
+        let executable = self
+            .executable
+            .lock()
+            .map_err(|e| e.to_string())?
+            .clone()
+            .ok_or("The build produced no executable")?;
+        let (child, readers) = spawn_piped(
+            Command::new(executable).current_dir(&self.case_dir),
+            &self.log,
+            None,
+        )
+        .map_err(|e| e.to_string())?;
+        self.child = child;
+        self.readers = readers;
+        self.run_started = Some(Instant::now());
+        Ok(())
+    }
+
+    pub(crate) fn is_running(&self) -> bool {
+        self.finished.is_none()
+    }
+
+    pub(crate) fn stop(&mut self) -> std::io::Result<()> {
+        self.child.kill()
+    }
+
+    /// Checks whether the process has exited. Once it has, waits for the
+    /// remaining output, starts the case executable after the build of a
+    /// run, and otherwise writes the whole log into the case directory.
+    pub(crate) fn poll(&mut self) {
+        if self.finished.is_some() {
+            return;
+        }
+        let mut status = match self.child.try_wait() {
+            Ok(None) => return,
+            Ok(Some(status)) => Ok(status),
+            Err(e) => Err(e.to_string()),
+        };
+        for reader in self.readers.drain(..) {
+            let _ = reader.join();
+        }
+        if self.command == CargoCommand::Run
+            && self.run_started.is_none()
+            && status.as_ref().is_ok_and(ExitStatus::success)
+        {
+            match self.spawn_executable() {
+                Ok(()) => return,
+                Err(e) => status = Err(e),
+            }
+        }
+        let elapsed = self.started.elapsed();
+        self.run_elapsed = self.run_started.map(|run_started| run_started.elapsed());
+        if let Err(e) = self.write_log(&status, elapsed)
+            && let Ok(mut log) = self.log.lock()
+        {
+            log.push(LogLine {
+                is_stderr: true,
+                text: format!("Could not write {}: {}", RUN_LOG_FILE_NAME, e),
+            });
+        }
+        self.finished = Some((status, elapsed));
+    }
+
+    fn write_log(
+        &self,
+        status: &Result<ExitStatus, String>,
+        elapsed: Duration,
+    ) -> std::io::Result<()> {
+        let mut content = format!("$ {}\n", self.command.get_label());
+        if let Ok(log) = self.log.lock() {
+            for line in log.iter() {
+                content.push_str(&line.text);
+                content.push('\n');
+            }
+        }
+        content.push_str(&format!(
+            "\n{} after {:.1} s\n",
+            get_status_literal(status),
+            elapsed.as_secs_f64()
+        ));
+        if let Some(run_elapsed) = self.run_elapsed {
+            content.push_str(&format!(
+                "Case executable ran for {:.1} s\n",
+                run_elapsed.as_secs_f64()
+            ));
+        }
+        std::fs::write(self.case_dir.join(RUN_LOG_FILE_NAME), content)
+    }
+}
+
+pub(crate) fn get_status_literal(status: &Result<ExitStatus, String>) -> String {
+    match status {
+        Ok(status) => match status.code() {
+            Some(code) => format!("Exited with code {}", code),
+            None => "Terminated by a signal".to_string(),
+        },
+        Err(e) => format!("Error: {}", e),
+    }
+}
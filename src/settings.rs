@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::naming::DEFAULT_CASE_NAME_PATTERN;
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct UserSettings {
+    pub(crate) use_lbflow_soa_location: bool,
+    pub(crate) lbflow_soa_location: String,
+    pub(crate) case_name_pattern: String,
+}
+
+impl Default for UserSettings {
+    fn default() -> Self {
+        UserSettings {
+            use_lbflow_soa_location: false,
+            lbflow_soa_location: String::new(),
+            case_name_pattern: DEFAULT_CASE_NAME_PATTERN.to_string(),
+        }
+    }
+}
+
+fn get_settings_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("lbflow_case_builder").join("settings.json"))
+}
+
+impl UserSettings {
+    pub(crate) fn load() -> Self {
+        get_settings_path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn save(&self) -> Result<(), String> {
+        let path = get_settings_path().ok_or("No user configuration directory")?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, content).map_err(|e| e.to_string())
+    }
+}
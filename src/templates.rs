@@ -0,0 +1,473 @@
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "gui")]
+use std::path::PathBuf;
+
+use crate::config::*;
+
+#[cfg(feature = "gui")]
+const WATER_KINEMATIC_VISCOSITY: f64 = 1.0e-6;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct CaseTemplate {
+    pub(crate) d_cfg: domain::GuiConfig,
+    pub(crate) m_cfg: momentum::GuiConfig,
+    pub(crate) s_cfg_vec: Vec<scalar::GuiConfig>,
+    pub(crate) c_cfg: CargoGuiConfig,
+    #[serde(default)]
+    pub(crate) j_cfg: job::GuiConfig,
+}
+
+#[cfg(feature = "gui")]
+impl CaseTemplate {
+    pub(crate) fn get_summary(&self) -> Vec<(String, String)> {
+        let dim_literal = match self.d_cfg.dim {
+            Dimensionality::D2 => "2D",
+            Dimensionality::D3 => "3D",
+        };
+        let grid_literal = match self.d_cfg.dim {
+            Dimensionality::D2 => format!("{} x {}", self.d_cfg.grid[0], self.d_cfg.grid[1]),
+            Dimensionality::D3 => format!(
+                "{} x {} x {}",
+                self.d_cfg.grid[0], self.d_cfg.grid[1], self.d_cfg.grid[2]
+            ),
+        };
+        let mut summary = vec![
+            ("Dimensions".to_string(), dim_literal.to_string()),
+            ("Grid".to_string(), grid_literal),
+            (
+                "Velocity set".to_string(),
+                self.m_cfg.velocity_set.get_label().to_string(),
+            ),
+            (
+                "Collision operator".to_string(),
+                self.m_cfg.collision_operator.get_label(),
+            ),
+            (
+                "Delta x / delta t".to_string(),
+                format!("{} m / {} s", self.m_cfg.delta_x, self.m_cfg.delta_t),
+            ),
+        ];
+        for boundary_face in BoundaryFaceGui::get_faces(self.d_cfg.dim) {
+            if let Some(face_bc) = self.m_cfg.get_face_bc(boundary_face) {
+                summary.push((
+                    boundary_face.to_literal(),
+                    face_bc.boundary_condition.get_label().to_string(),
+                ));
+            }
+        }
+        let scalar_names = self
+            .s_cfg_vec
+            .iter()
+            .map(|s_cfg| s_cfg.name.clone())
+            .collect::<Vec<String>>();
+        summary.push(("Scalars".to_string(), scalar_names.join(", ")));
+        summary
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct UserTemplate {
+    pub(crate) name: String,
+    pub(crate) description: String,
+    pub(crate) case: CaseTemplate,
+}
+
+#[cfg(feature = "gui")]
+pub(crate) fn get_user_templates_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("lbflow_case_builder").join("templates"))
+}
+
+#[cfg(feature = "gui")]
+/// Maps a template name to its file in the user library. ASCII lowercase
+/// letters, digits and `-` are kept and every other byte is written as `_`
+/// followed by its two hex digits, so that distinct names (`a b`, `a.b`,
+/// `a_b`, `A`) never share a file, even on case-insensitive file systems.
+fn get_user_template_path(name: &str) -> Result<PathBuf, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Template name empty".to_string());
+    }
+    let mut file_stem = String::with_capacity(name.len());
+    for byte in name.bytes() {
+        if byte.is_ascii_lowercase() || byte.is_ascii_digit() || byte == b'-' {
+            file_stem.push(byte as char);
+        } else {
+            file_stem.push_str(&format!("_{:02x}", byte));
+        }
+    }
+    let dir = get_user_templates_dir().ok_or("No user configuration directory")?;
+    Ok(dir.join(format!("{}.json", file_stem)))
+}
+
+#[cfg(feature = "gui")]
+/// Returns the file that older versions, which replaced every character
+/// outside `[A-Za-z0-9-]` with `_`, saved the template under, when that file
+/// exists and holds a template with this exact name.
+fn get_legacy_user_template_path(name: &str) -> Option<PathBuf> {
+    let file_stem = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+    let path = get_user_templates_dir()?.join(format!("{}.json", file_stem));
+    let content = std::fs::read_to_string(&path).ok()?;
+    let user_template = serde_json::from_str::<UserTemplate>(&content).ok()?;
+    (user_template.name == name).then_some(path)
+}
+
+#[cfg(feature = "gui")]
+impl UserTemplate {
+    pub(crate) fn save(&self) -> Result<PathBuf, String> {
+        let path = get_user_template_path(&self.name)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(&path, content).map_err(|e| e.to_string())?;
+        if let Some(legacy_path) = get_legacy_user_template_path(&self.name)
+            && legacy_path != path
+        {
+            std::fs::remove_file(legacy_path).map_err(|e| e.to_string())?;
+        }
+        Ok(path)
+    }
+
+    pub(crate) fn delete(&self) -> Result<(), String> {
+        let path = get_user_template_path(&self.name)?;
+        if !path.exists()
+            && let Some(legacy_path) = get_legacy_user_template_path(&self.name)
+        {
+            return std::fs::remove_file(legacy_path).map_err(|e| e.to_string());
+        }
+        std::fs::remove_file(path).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(feature = "gui")]
+/// Reads every template of the user library, skipping files that cannot be
+/// parsed, sorted by name.
+pub(crate) fn load_user_templates() -> Result<Vec<UserTemplate>, String> {
+    let Some(dir) = get_user_templates_dir() else {
+        return Ok(vec![]);
+    };
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+    let mut user_templates = vec![];
+    for entry in std::fs::read_dir(&dir).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "json")
+            && let Ok(content) = std::fs::read_to_string(&path)
+            && let Ok(user_template) = serde_json::from_str::<UserTemplate>(&content)
+        {
+            user_templates.push(user_template);
+        }
+    }
+    user_templates.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(user_templates)
+}
+
+#[cfg(feature = "gui")]
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub(crate) enum BuiltInTemplate {
+    LidDrivenCavity,
+    PoiseuilleChannel,
+    CouetteFlow,
+    TaylorGreenVortex,
+    FlowPastCylinder,
+    GaussianPulse,
+}
+
+#[cfg(feature = "gui")]
+impl BuiltInTemplate {
+    pub(crate) const ALL: [BuiltInTemplate; 6] = [
+        BuiltInTemplate::LidDrivenCavity,
+        BuiltInTemplate::PoiseuilleChannel,
+        BuiltInTemplate::CouetteFlow,
+        BuiltInTemplate::TaylorGreenVortex,
+        BuiltInTemplate::FlowPastCylinder,
+        BuiltInTemplate::GaussianPulse,
+    ];
+
+    pub(crate) fn get_name(self) -> &'static str {
+        match self {
+            BuiltInTemplate::LidDrivenCavity => "Lid-driven cavity",
+            BuiltInTemplate::PoiseuilleChannel => "Poiseuille channel",
+            BuiltInTemplate::CouetteFlow => "Couette flow",
+            BuiltInTemplate::TaylorGreenVortex => "Taylor-Green vortex",
+            BuiltInTemplate::FlowPastCylinder => "Flow past a cylinder",
+            BuiltInTemplate::GaussianPulse => "Advection-diffusion of a Gaussian pulse",
+        }
+    }
+
+    pub(crate) fn get_description(self) -> &'static str {
+        match self {
+            BuiltInTemplate::LidDrivenCavity => {
+                "Re = 100 square cavity driven by the North lid. Expect a primary vortex \
+                 slightly above the centre and two weak corner vortices at the bottom, \
+                 matching the centreline profiles of Ghia et al. (1982)."
+            }
+            BuiltInTemplate::PoiseuilleChannel => {
+                "Pressure-driven flow between two plates. Expect a parabolic velocity \
+                 profile with u_max = dp H^2 / (8 rho nu L) and a linear pressure drop."
+            }
+            BuiltInTemplate::CouetteFlow => {
+                "Periodic channel with a moving North wall. Expect a linear velocity \
+                 profile from 0 at the South wall to the wall velocity at the North wall."
+            }
+            BuiltInTemplate::TaylorGreenVortex => {
+                "Fully periodic decaying vortex array. Expect the velocity amplitude to \
+                 decay as exp(-2 nu k^2 t) with k = 2 pi / n while the pattern stays fixed."
+            }
+            BuiltInTemplate::FlowPastCylinder => {
+                "Re = 100 channel flow past a cylinder with a generated map. Expect a \
+                 periodic Karman vortex street with a Strouhal number close to 0.2 \
+                 (slightly higher because of the 20% blockage)."
+            }
+            BuiltInTemplate::GaussianPulse => {
+                "Gaussian pulse carried by a uniform periodic flow. Expect the centre to \
+                 move with the flow velocity and the variance to grow as s0^2 + 2 D t."
+            }
+        }
+    }
+
+    pub(crate) fn get_case_name(self) -> &'static str {
+        match self {
+            BuiltInTemplate::LidDrivenCavity => "lid_driven_cavity",
+            BuiltInTemplate::PoiseuilleChannel => "poiseuille_channel",
+            BuiltInTemplate::CouetteFlow => "couette_flow",
+            BuiltInTemplate::TaylorGreenVortex => "taylor_green_vortex",
+            BuiltInTemplate::FlowPastCylinder => "flow_past_cylinder",
+            BuiltInTemplate::GaussianPulse => "gaussian_pulse",
+        }
+    }
+
+    pub(crate) fn get_template(self) -> CaseTemplate {
+        let c_cfg = CargoGuiConfig {
+            case_name: self.get_case_name().to_string(),
+            ..CargoGuiConfig::default()
+        };
+        let (d_cfg, m_cfg, s_cfg_vec) = match self {
+            BuiltInTemplate::LidDrivenCavity => get_lid_driven_cavity(),
+            BuiltInTemplate::PoiseuilleChannel => get_poiseuille_channel(),
+            BuiltInTemplate::CouetteFlow => get_couette_flow(),
+            BuiltInTemplate::TaylorGreenVortex => get_taylor_green_vortex(),
+            BuiltInTemplate::FlowPastCylinder => get_flow_past_cylinder(),
+            BuiltInTemplate::GaussianPulse => get_gaussian_pulse(),
+        };
+        CaseTemplate {
+            d_cfg,
+            m_cfg,
+            s_cfg_vec,
+            c_cfg,
+            j_cfg: job::GuiConfig::default(),
+        }
+    }
+}
+
+#[cfg(feature = "gui")]
+type TemplateConfig = (
+    domain::GuiConfig,
+    momentum::GuiConfig,
+    Vec<scalar::GuiConfig>,
+);
+
+#[cfg(feature = "gui")]
+fn get_d2_domain(nx: usize, ny: usize, node_type_mask: NodeTypeMaskGui) -> domain::GuiConfig {
+    domain::GuiConfig {
+        dim: Dimensionality::D2,
+        grid: [nx, ny, 1],
+        node_type_mask,
+    }
+}
+
+#[cfg(feature = "gui")]
+fn get_momentum(
+    tau: f64,
+    boundary_conditions: [momentum::BoundaryConditionGui; 4],
+) -> momentum::GuiConfig {
+    let delta_x = 1.0e-3;
+    let lattice_viscosity = (tau - 0.5) / 3.0;
+    let [west, east, south, north] = boundary_conditions;
+    momentum::GuiConfig {
+        velocity_set: VelocitySetGui::D2Q9,
+        collision_operator: CollisionOperatorGui::BGK { tau },
+        delta_x,
+        delta_t: lattice_viscosity * delta_x * delta_x / WATER_KINEMATIC_VISCOSITY,
+        boundary_conditions: vec![
+            momentum::FaceBC {
+                boundary_face: BoundaryFaceGui::West,
+                boundary_condition: west,
+            },
+            momentum::FaceBC {
+                boundary_face: BoundaryFaceGui::East,
+                boundary_condition: east,
+            },
+            momentum::FaceBC {
+                boundary_face: BoundaryFaceGui::South,
+                boundary_condition: south,
+            },
+            momentum::FaceBC {
+                boundary_face: BoundaryFaceGui::North,
+                boundary_condition: north,
+            },
+        ],
+        ..momentum::GuiConfig::default()
+    }
+}
+
+#[cfg(feature = "gui")]
+fn get_lid_driven_cavity() -> TemplateConfig {
+    // Re = u_lid * n / nu = 0.1 * 128 / 0.128 = 100
+    let d_cfg = get_d2_domain(128, 128, NodeTypeMaskGui::OnlyFluidNodes);
+    let m_cfg = get_momentum(
+        0.884,
+        [
+            momentum::BoundaryConditionGui::NoSlip,
+            momentum::BoundaryConditionGui::NoSlip,
+            momentum::BoundaryConditionGui::NoSlip,
+            momentum::BoundaryConditionGui::BounceBack {
+                rho: 1.0,
+                ux: 0.1,
+                uy: 0.0,
+                uz: 0.0,
+            },
+        ],
+    );
+    (d_cfg, m_cfg, vec![])
+}
+
+#[cfg(feature = "gui")]
+fn get_poiseuille_channel() -> TemplateConfig {
+    let d_cfg = get_d2_domain(200, 51, NodeTypeMaskGui::OnlyFluidNodes);
+    let m_cfg = get_momentum(
+        0.8,
+        [
+            momentum::BoundaryConditionGui::AntiBounceBack { rho: 1.001 },
+            momentum::BoundaryConditionGui::AntiBounceBack { rho: 1.0 },
+            momentum::BoundaryConditionGui::NoSlip,
+            momentum::BoundaryConditionGui::NoSlip,
+        ],
+    );
+    (d_cfg, m_cfg, vec![])
+}
+
+#[cfg(feature = "gui")]
+fn get_couette_flow() -> TemplateConfig {
+    let d_cfg = get_d2_domain(16, 64, NodeTypeMaskGui::OnlyFluidNodes);
+    let m_cfg = get_momentum(
+        0.8,
+        [
+            momentum::BoundaryConditionGui::Periodic,
+            momentum::BoundaryConditionGui::Periodic,
+            momentum::BoundaryConditionGui::NoSlip,
+            momentum::BoundaryConditionGui::BounceBack {
+                rho: 1.0,
+                ux: 0.05,
+                uy: 0.0,
+                uz: 0.0,
+            },
+        ],
+    );
+    (d_cfg, m_cfg, vec![])
+}
+
+#[cfg(feature = "gui")]
+fn get_taylor_green_vortex() -> TemplateConfig {
+    let d_cfg = get_d2_domain(128, 128, NodeTypeMaskGui::OnlyFluidNodes);
+    let mut m_cfg = get_momentum(
+        0.6,
+        [
+            momentum::BoundaryConditionGui::Periodic,
+            momentum::BoundaryConditionGui::Periodic,
+            momentum::BoundaryConditionGui::Periodic,
+            momentum::BoundaryConditionGui::Periodic,
+        ],
+    );
+    // p = -rho u0^2 / 4 (cos(2kx) + cos(2ky)) with rho = 1 + p / cs^2
+    m_cfg.initial_density = momentum::InitialDensityGui::Expression {
+        rho: "1 - 3 * 0.04^2 / 4 * (cos(4 * pi * x / nx) + cos(4 * pi * y / ny))".to_string(),
+        coordinates: CoordinatesGui::Lattice,
+    };
+    m_cfg.initial_velocity = momentum::InitialVelocityGui::Expression {
+        ux: "-0.04 * cos(2 * pi * x / nx) * sin(2 * pi * y / ny)".to_string(),
+        uy: "0.04 * sin(2 * pi * x / nx) * cos(2 * pi * y / ny)".to_string(),
+        uz: "0".to_string(),
+        coordinates: CoordinatesGui::Lattice,
+    };
+    (d_cfg, m_cfg, vec![])
+}
+
+#[cfg(feature = "gui")]
+fn get_flow_past_cylinder() -> TemplateConfig {
+    // Re = u * D / nu = 0.05 * 20 / 0.01 = 100
+    let d_cfg = get_d2_domain(
+        400,
+        100,
+        NodeTypeMaskGui::Expression {
+            solid: "10^2 - ((x - nx / 5)^2 + (y - ny / 2)^2)".to_string(),
+            coordinates: CoordinatesGui::Lattice,
+        },
+    );
+    let mut m_cfg = get_momentum(
+        0.53,
+        [
+            momentum::BoundaryConditionGui::BounceBack {
+                rho: 1.0,
+                ux: 0.05,
+                uy: 0.0,
+                uz: 0.0,
+            },
+            momentum::BoundaryConditionGui::AntiBounceBack { rho: 1.0 },
+            momentum::BoundaryConditionGui::NoSlip,
+            momentum::BoundaryConditionGui::NoSlip,
+        ],
+    );
+    m_cfg.initial_velocity = momentum::InitialVelocityGui::Uniform {
+        ux: 0.05,
+        uy: 0.0,
+        uz: 0.0,
+    };
+    (d_cfg, m_cfg, vec![])
+}
+
+#[cfg(feature = "gui")]
+fn get_gaussian_pulse() -> TemplateConfig {
+    let d_cfg = get_d2_domain(200, 100, NodeTypeMaskGui::OnlyFluidNodes);
+    let mut m_cfg = get_momentum(
+        0.8,
+        [
+            momentum::BoundaryConditionGui::Periodic,
+            momentum::BoundaryConditionGui::Periodic,
+            momentum::BoundaryConditionGui::Periodic,
+            momentum::BoundaryConditionGui::Periodic,
+        ],
+    );
+    m_cfg.initial_velocity = momentum::InitialVelocityGui::Uniform {
+        ux: 0.05,
+        uy: 0.0,
+        uz: 0.0,
+    };
+    let mut s_cfg = scalar::GuiConfig {
+        name: "c".to_string(),
+        collision_operator: CollisionOperatorGui::BGK { tau: 0.65 },
+        velocity_set: VelocitySetGui::D2Q5,
+        initial_scalar_value: scalar::InitialScalarValueGui::Expression {
+            value: "exp(-((x - nx / 4)^2 + (y - ny / 2)^2) / (2 * 8^2))".to_string(),
+            coordinates: CoordinatesGui::Lattice,
+        },
+        ..scalar::GuiConfig::default()
+    };
+    for face_bc in &mut s_cfg.boundary_conditions {
+        face_bc.boundary_condition = scalar::BoundaryConditionGui::Periodic;
+    }
+    (d_cfg, m_cfg, vec![s_cfg])
+}
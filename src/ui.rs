@@ -1,7 +1,32 @@
+mod browser;
+mod build;
+mod compare;
+mod estimate;
+mod geometry;
+mod history;
+mod preview;
+mod run;
+mod session;
+mod templates;
+
 use eframe::egui;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::config::*;
+use crate::dependency::{self, SourceCodeInfo};
+use crate::naming::NamingScheme;
+use crate::settings::UserSettings;
+use crate::writer;
+use browser::CaseBrowserGui;
+use build::PendingBuild;
+use compare::CompareGui;
+use estimate::EstimatorGui;
+use geometry::GeometryAnalysisGui;
+use history::HistoryGui;
+use preview::DomainPreviewGui;
+use run::RunGui;
+use session::SessionGui;
+use templates::TemplateLibraryGui;
 
 pub struct GuiApp {
     d_cfg: domain::GuiConfig,
@@ -9,8 +34,22 @@ pub struct GuiApp {
     n_scalars: usize,
     s_cfg_vec: Vec<scalar::GuiConfig>,
     c_cfg: CargoGuiConfig,
+    j_cfg: job::GuiConfig,
     parent_dir: String,
     status: String,
+    geometry_analysis: GeometryAnalysisGui,
+    domain_preview: DomainPreviewGui,
+    template_note: String,
+    template_library: TemplateLibraryGui,
+    pending_build: Option<PendingBuild>,
+    run: RunGui,
+    source_code_info: Option<(PathBuf, Result<SourceCodeInfo, String>)>,
+    settings: UserSettings,
+    estimator: EstimatorGui,
+    history: HistoryGui,
+    session: SessionGui,
+    compare: CompareGui,
+    case_browser: CaseBrowserGui,
 }
 
 impl Default for GuiApp {
@@ -21,15 +60,35 @@ impl Default for GuiApp {
             n_scalars: 0,
             s_cfg_vec: vec![],
             c_cfg: CargoGuiConfig::default(),
+            j_cfg: job::GuiConfig::default(),
             parent_dir: String::from("./cases/case_000"),
             status: String::new(),
+            geometry_analysis: GeometryAnalysisGui::default(),
+            domain_preview: DomainPreviewGui::default(),
+            template_note: String::new(),
+            template_library: TemplateLibraryGui::default(),
+            pending_build: None,
+            run: RunGui::default(),
+            source_code_info: None,
+            settings: UserSettings::default(),
+            estimator: EstimatorGui::default(),
+            history: HistoryGui::default(),
+            session: SessionGui::default(),
+            compare: CompareGui::default(),
+            case_browser: CaseBrowserGui::default(),
         }
     }
 }
 
 impl GuiApp {
-    pub fn new() -> Self {
-        Self::default()
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let mut app = Self {
+            settings: UserSettings::load(),
+            ..Self::default()
+        };
+        app.update_source_code_path();
+        app.load_session(cc.storage);
+        app
     }
 
     fn ui_dim(&mut self, ui: &mut egui::Ui) {
@@ -68,7 +127,25 @@ impl GuiApp {
                 NodeTypeMaskGui::FromMapFile,
                 "From map file",
             );
+            let is_expression = matches!(
+                self.d_cfg.node_type_mask,
+                NodeTypeMaskGui::Expression { .. }
+            );
+            if ui
+                .selectable_label(is_expression, "Solid expression")
+                .clicked()
+                && !is_expression
+            {
+                self.d_cfg.node_type_mask = NodeTypeMaskGui::Expression {
+                    solid: "-1".to_string(),
+                    coordinates: CoordinatesGui::Lattice,
+                };
+            }
         });
+        if let NodeTypeMaskGui::Expression { solid, coordinates } = &mut self.d_cfg.node_type_mask {
+            ui_expression(ui, "Solid where positive:", solid);
+            ui.horizontal(|ui| ui_coordinates(ui, coordinates));
+        }
     }
 
     fn _ui_velocity_set(&mut self, ui: &mut egui::Ui) {
@@ -146,12 +223,19 @@ impl GuiApp {
                 momentum::InitialDensityGui::FromFile { file_path } => file_path.clone(),
                 _ => "./pre_processing/density.csv".to_string(),
             };
+            let (cur_rho_expression, cur_coordinates) = match &self.m_cfg.initial_density {
+                momentum::InitialDensityGui::Expression { rho, coordinates } => {
+                    (rho.clone(), *coordinates)
+                }
+                _ => (cur_rho.to_string(), CoordinatesGui::Lattice),
+            };
 
             egui::ComboBox::from_id_salt("initial_density_combo_box")
                 .selected_text(match &self.m_cfg.initial_density {
                     momentum::InitialDensityGui::Uniform { rho: _ } => "Uniform",
                     momentum::InitialDensityGui::FromTimeStep { time_step: _ } => "From time step",
                     momentum::InitialDensityGui::FromFile { file_path: _ } => "From file",
+                    momentum::InitialDensityGui::Expression { .. } => "Expression",
                 })
                 .show_ui(ui, |ui| {
                     ui.selectable_value(
@@ -173,6 +257,14 @@ impl GuiApp {
                         },
                         "From file",
                     );
+                    ui.selectable_value(
+                        &mut self.m_cfg.initial_density,
+                        momentum::InitialDensityGui::Expression {
+                            rho: cur_rho_expression.clone(),
+                            coordinates: cur_coordinates,
+                        },
+                        "Expression",
+                    );
                 });
         });
         ui.horizontal(|ui| match &mut self.m_cfg.initial_density {
@@ -192,6 +284,12 @@ impl GuiApp {
                 ui.label("File path:");
                 ui.text_edit_singleline(file_path);
             }
+            momentum::InitialDensityGui::Expression { rho, coordinates } => {
+                ui.vertical(|ui| {
+                    ui_expression(ui, "rho:", rho);
+                    ui.horizontal(|ui| ui_coordinates(ui, coordinates));
+                });
+            }
         });
     }
 
@@ -210,6 +308,20 @@ impl GuiApp {
                 momentum::InitialVelocityGui::FromFile { file_path } => file_path.clone(),
                 _ => "./pre_processing/velocity.csv".to_string(),
             };
+            let cur_vel_expression = match &self.m_cfg.initial_velocity {
+                momentum::InitialVelocityGui::Expression {
+                    ux,
+                    uy,
+                    uz,
+                    coordinates,
+                } => (ux.clone(), uy.clone(), uz.clone(), *coordinates),
+                _ => (
+                    cur_ux.to_string(),
+                    cur_uy.to_string(),
+                    cur_uz.to_string(),
+                    CoordinatesGui::Lattice,
+                ),
+            };
             egui::ComboBox::from_id_salt("initial_velocity_combo_box")
                 .selected_text(match &self.m_cfg.initial_velocity {
                     momentum::InitialVelocityGui::Uniform {
@@ -219,6 +331,7 @@ impl GuiApp {
                     } => "Uniform",
                     momentum::InitialVelocityGui::FromTimeStep { time_step: _ } => "From time step",
                     momentum::InitialVelocityGui::FromFile { file_path: _ } => "From file",
+                    momentum::InitialVelocityGui::Expression { .. } => "Expression",
                 })
                 .show_ui(ui, |ui| {
                     ui.selectable_value(
@@ -244,6 +357,16 @@ impl GuiApp {
                         },
                         "From file",
                     );
+                    ui.selectable_value(
+                        &mut self.m_cfg.initial_velocity,
+                        momentum::InitialVelocityGui::Expression {
+                            ux: cur_vel_expression.0.clone(),
+                            uy: cur_vel_expression.1.clone(),
+                            uz: cur_vel_expression.2.clone(),
+                            coordinates: cur_vel_expression.3,
+                        },
+                        "Expression",
+                    );
                 });
         });
         ui.horizontal(|ui| match &mut self.m_cfg.initial_velocity {
@@ -271,6 +394,21 @@ impl GuiApp {
                 ui.label("File path:");
                 ui.text_edit_singleline(file_path);
             }
+            momentum::InitialVelocityGui::Expression {
+                ux,
+                uy,
+                uz,
+                coordinates,
+            } => {
+                ui.vertical(|ui| {
+                    ui_expression(ui, "ux:", ux);
+                    ui_expression(ui, "uy:", uy);
+                    if self.d_cfg.dim == Dimensionality::D3 {
+                        ui_expression(ui, "uz:", uz);
+                    }
+                    ui.horizontal(|ui| ui_coordinates(ui, coordinates));
+                });
+            }
         });
     }
 
@@ -298,7 +436,15 @@ impl GuiApp {
                 6,
             ),
         };
-        for i in 0..number_of_faces {
+        let dim = self.d_cfg.dim;
+        let bulk_id = egui::Id::new("m_bulk_boundary_conditions");
+        let (mut bulk_boundary_condition, mut selected_faces) = ui.memory(|mem| {
+            mem.data
+                .get_temp::<(momentum::BoundaryConditionGui, Vec<bool>)>(bulk_id)
+                .unwrap_or((momentum::BoundaryConditionGui::NoSlip, vec![]))
+        });
+        selected_faces.resize(number_of_faces, false);
+        for (i, is_selected) in selected_faces.iter_mut().enumerate() {
             if i >= self.m_cfg.boundary_conditions.len() {
                 self.m_cfg.boundary_conditions.push(momentum::FaceBC {
                     boundary_face: possible_faces[i],
@@ -307,6 +453,7 @@ impl GuiApp {
             }
             let face_bc = &mut self.m_cfg.boundary_conditions[i];
             ui.horizontal(|ui| {
+                ui.checkbox(is_selected, "");
                 ui.label(format!("Face {}:", i));
                 egui::ComboBox::from_id_salt(format!("boundary_face_combo_box_{}", i))
                     .selected_text(face_bc.boundary_face.to_literal())
@@ -319,73 +466,98 @@ impl GuiApp {
                             );
                         }
                     });
-                let (cur_bb_rho, cur_ux, cur_uy, cur_uz) = match &face_bc.boundary_condition {
-                    momentum::BoundaryConditionGui::BounceBack { rho, ux, uy, uz } => {
-                        (*rho, *ux, *uy, *uz)
-                    }
-                    _ => (1.0, 0.0, 0.0, 0.0),
-                };
-                let cur_abb_rho = match &face_bc.boundary_condition {
-                    momentum::BoundaryConditionGui::AntiBounceBack { rho } => *rho,
-                    _ => 1.0,
-                };
-                egui::ComboBox::from_id_salt(format!("boundary_condition_combo_box_{}", i))
-                    .selected_text(match &face_bc.boundary_condition {
-                        momentum::BoundaryConditionGui::NoSlip => "No-slip",
-                        momentum::BoundaryConditionGui::BounceBack { .. } => "Bounce-back",
-                        momentum::BoundaryConditionGui::AntiBounceBack { .. } => "Anti-bounce-back",
-                        momentum::BoundaryConditionGui::Periodic => "Periodic",
-                    })
-                    .show_ui(ui, |ui| {
-                        ui.selectable_value(
-                            &mut face_bc.boundary_condition,
-                            momentum::BoundaryConditionGui::NoSlip,
-                            "No-slip",
-                        );
-                        ui.selectable_value(
-                            &mut face_bc.boundary_condition,
-                            momentum::BoundaryConditionGui::BounceBack {
-                                rho: cur_bb_rho,
-                                ux: cur_ux,
-                                uy: cur_uy,
-                                uz: cur_uz,
-                            },
-                            "Bounce-back",
-                        );
-                        ui.selectable_value(
-                            &mut face_bc.boundary_condition,
-                            momentum::BoundaryConditionGui::AntiBounceBack { rho: cur_abb_rho },
-                            "Anti-bounce-back",
-                        );
-                        ui.selectable_value(
-                            &mut face_bc.boundary_condition,
-                            momentum::BoundaryConditionGui::Periodic,
-                            "Periodic",
-                        );
-                    });
-                match &mut face_bc.boundary_condition {
-                    momentum::BoundaryConditionGui::BounceBack { rho, ux, uy, uz } => {
-                        ui.label("rho:");
-                        ui.add(egui::DragValue::new(rho).speed(0.01));
-                        ui.label("ux:");
-                        ui.add(egui::DragValue::new(ux).speed(0.01));
-                        ui.label("uy:");
-                        ui.add(egui::DragValue::new(uy).speed(0.01));
-                        if self.d_cfg.dim == Dimensionality::D3 {
-                            ui.label("uz:");
-                            ui.add(egui::DragValue::new(uz).speed(0.01));
-                        } else {
-                            *uz = 0.0;
-                        }
-                    }
-                    momentum::BoundaryConditionGui::AntiBounceBack { rho } => {
-                        ui.label("rho:");
-                        ui.add(egui::DragValue::new(rho).speed(0.01));
-                    }
-                    _ => {}
+                ui_m_boundary_condition(
+                    ui,
+                    format!("boundary_condition_combo_box_{}", i),
+                    &mut face_bc.boundary_condition,
+                    dim,
+                );
+            });
+        }
+        ui.horizontal(|ui| {
+            ui.label("Set");
+            ui_m_boundary_condition(
+                ui,
+                "m_bulk_boundary_condition_combo_box".to_string(),
+                &mut bulk_boundary_condition,
+                dim,
+            );
+            if ui.button("on all faces").clicked() {
+                self.m_cfg
+                    .set_boundary_condition(&possible_faces, &bulk_boundary_condition);
+            }
+            let faces = possible_faces
+                .iter()
+                .zip(&selected_faces)
+                .filter(|(_, is_selected)| **is_selected)
+                .map(|(face, _)| *face)
+                .collect::<Vec<BoundaryFaceGui>>();
+            if ui
+                .add_enabled(!faces.is_empty(), egui::Button::new("on selected faces"))
+                .clicked()
+            {
+                self.m_cfg
+                    .set_boundary_condition(&faces, &bulk_boundary_condition);
+            }
+        });
+        ui.memory_mut(|mem| {
+            mem.data
+                .insert_temp(bulk_id, (bulk_boundary_condition, selected_faces))
+        });
+        self.ui_m_boundary_patches(ui);
+    }
+
+    /// Why the patches of the case cannot be generated, if they cannot.
+    fn get_patch_error(&self) -> Option<String> {
+        let has_patches = !self.m_cfg.boundary_patches.is_empty()
+            || self
+                .s_cfg_vec
+                .iter()
+                .any(|s_cfg| !s_cfg.boundary_patches.is_empty());
+        if !has_patches {
+            return None;
+        }
+        self.get_case_template().validate_boundary_patches().err()
+    }
+
+    fn ui_m_boundary_patches(&mut self, ui: &mut egui::Ui) {
+        let (grid, dim) = (self.d_cfg.grid, self.d_cfg.dim);
+        ui.label("Patches:").on_hover_text(PATCH_LIMITATION_NOTE);
+        let mut removed = None;
+        for (i, patch_bc) in self.m_cfg.boundary_patches.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(format!("Patch {}:", i));
+                ui_face_patch(
+                    ui,
+                    format!("m_patch_face_combo_box_{}", i),
+                    &mut patch_bc.patch,
+                    grid,
+                    dim,
+                );
+                ui_m_boundary_condition(
+                    ui,
+                    format!("m_patch_condition_combo_box_{}", i),
+                    &mut patch_bc.boundary_condition,
+                    dim,
+                );
+                if ui.button("Remove").clicked() {
+                    removed = Some(i);
                 }
             });
         }
+        if let Some(i) = removed {
+            self.m_cfg.boundary_patches.remove(i);
+        }
+        if ui.button("Add patch").clicked() {
+            self.m_cfg.boundary_patches.push(momentum::PatchBC {
+                patch: FacePatchGui::new(BoundaryFaceGui::West, grid),
+                boundary_condition: momentum::BoundaryConditionGui::NoSlip,
+            });
+        }
+        if let Some(e) = self.get_patch_error() {
+            ui.colored_label(ui.visuals().error_fg_color, e)
+                .on_hover_text(PATCH_LIMITATION_NOTE);
+        }
     }
 
     fn ui_domain(&mut self, ui: &mut egui::Ui) {
@@ -421,18 +593,207 @@ impl GuiApp {
         self.ui_initial_velocity(ui);
     }
 
+    fn ui_job_script(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Job script");
+        self.j_cfg.ui_job_script(ui);
+    }
+
     fn ui_case_name(&mut self, ui: &mut egui::Ui) {
+        let naming_scheme = NamingScheme::parse(&self.settings.case_name_pattern);
+        let parent_dir = PathBuf::from(&self.parent_dir);
         ui.horizontal(|ui| {
             ui.label("Case name:");
             ui.text_edit_singleline(&mut self.c_cfg.case_name);
+            if let Ok(naming_scheme) = &naming_scheme {
+                if ui.button("Next series").clicked() {
+                    self.c_cfg.case_name = naming_scheme.get_next_series(&parent_dir);
+                }
+                if ui.button("Next variant").clicked() {
+                    self.c_cfg.case_name =
+                        naming_scheme.get_next_variant(&parent_dir, &self.c_cfg.case_name);
+                }
+            }
+        });
+        if self.get_case_dir().exists() {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                "A directory with this name already exists",
+            );
+        }
+        let mut pattern_changed = false;
+        ui.horizontal(|ui| {
+            ui.label("Naming pattern:");
+            pattern_changed = ui
+                .text_edit_singleline(&mut self.settings.case_name_pattern)
+                .on_hover_text("{series} and {variant}, optionally padded as in {series:3}")
+                .lost_focus();
+            if let Err(e) = &naming_scheme {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+            }
+        });
+        if pattern_changed && let Err(e) = self.settings.save() {
+            self.status = format!("Error: {}", e);
+        }
+    }
+
+    fn update_source_code_path(&mut self) {
+        if !self.settings.use_lbflow_soa_location {
+            return;
+        }
+        if let Some(relative_path) = dependency::get_relative_path(
+            &self.get_case_dir(),
+            Path::new(&self.settings.lbflow_soa_location),
+        ) {
+            self.c_cfg.source_code_path = relative_path;
+        }
+    }
+
+    fn ui_dependency_source(&mut self, ui: &mut egui::Ui) {
+        let dependency_source = &mut self.c_cfg.dependency_source;
+        ui.horizontal(|ui| {
+            ui.label("lbflow_soa source:");
+            egui::ComboBox::from_id_salt("dependency_source")
+                .selected_text(dependency_source.get_label())
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(dependency_source, DependencySourceGui::Path, "Path");
+                    let is_git = matches!(dependency_source, DependencySourceGui::Git { .. });
+                    if ui.selectable_label(is_git, "Git").clicked() && !is_git {
+                        *dependency_source = DependencySourceGui::Git {
+                            url: String::new(),
+                            reference: GitReferenceGui::Tag,
+                            value: format!("v{}", LBFLOW_SOA_VERSION),
+                        };
+                    }
+                    let is_registry =
+                        matches!(dependency_source, DependencySourceGui::Registry { .. });
+                    if ui.selectable_label(is_registry, "Registry").clicked() && !is_registry {
+                        *dependency_source = DependencySourceGui::Registry {
+                            version: LBFLOW_SOA_VERSION.to_string(),
+                        };
+                    }
+                });
+        });
+        match dependency_source {
+            DependencySourceGui::Path => self.ui_source_code_path(ui),
+            DependencySourceGui::Git {
+                url,
+                reference,
+                value,
+            } => {
+                ui.horizontal(|ui| {
+                    ui.label("Repository URL:");
+                    ui.text_edit_singleline(url);
+                });
+                ui.horizontal(|ui| {
+                    ui.selectable_value(reference, GitReferenceGui::Tag, "Tag");
+                    ui.selectable_value(reference, GitReferenceGui::Rev, "Rev");
+                    ui.text_edit_singleline(value);
+                });
+            }
+            DependencySourceGui::Registry { version } => {
+                ui.horizontal(|ui| {
+                    ui.label("Version:");
+                    ui.text_edit_singleline(version);
+                });
+            }
+        }
+    }
+
+    fn ui_release_profile(&mut self, ui: &mut egui::Ui) {
+        let release_profile = &mut self.c_cfg.release_profile;
+        ui.horizontal(|ui| {
+            ui.label("LTO:");
+            for lto in [LtoGui::Off, LtoGui::Thin, LtoGui::Fat] {
+                ui.selectable_value(&mut release_profile.lto, lto, lto.get_label());
+            }
+        });
+        ui.horizontal(|ui| {
+            let mut has_codegen_units = release_profile.codegen_units.is_some();
+            if ui
+                .checkbox(&mut has_codegen_units, "Codegen units:")
+                .changed()
+            {
+                release_profile.codegen_units = has_codegen_units.then_some(1);
+            }
+            if let Some(codegen_units) = &mut release_profile.codegen_units {
+                ui.add(egui::DragValue::new(codegen_units).range(1..=256));
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.checkbox(&mut release_profile.panic_abort, "panic = \"abort\"");
+            ui.checkbox(&mut release_profile.debug, "Debug symbols");
+            ui.checkbox(&mut self.c_cfg.target_cpu_native, "target-cpu=native");
         });
     }
 
     fn ui_source_code_path(&mut self, ui: &mut egui::Ui) {
+        let mut settings_changed = false;
         ui.horizontal(|ui| {
-            ui.label("Source code path:");
-            ui.text_edit_singleline(&mut self.c_cfg.source_code_path);
+            ui.label("lbflow_soa location:");
+            settings_changed |= ui
+                .selectable_value(
+                    &mut self.settings.use_lbflow_soa_location,
+                    false,
+                    "Relative to the case",
+                )
+                .changed();
+            settings_changed |= ui
+                .selectable_value(
+                    &mut self.settings.use_lbflow_soa_location,
+                    true,
+                    "Absolute location",
+                )
+                .changed();
         });
+        if self.settings.use_lbflow_soa_location {
+            ui.horizontal(|ui| {
+                ui.label("Absolute location:");
+                settings_changed |= ui
+                    .text_edit_singleline(&mut self.settings.lbflow_soa_location)
+                    .lost_focus();
+            });
+            self.update_source_code_path();
+            ui.horizontal(|ui| {
+                ui.label("Source code path:");
+                ui.label(&self.c_cfg.source_code_path);
+            });
+        } else {
+            ui.horizontal(|ui| {
+                ui.label("Source code path:");
+                ui.text_edit_singleline(&mut self.c_cfg.source_code_path);
+            });
+        }
+        if settings_changed && let Err(e) = self.settings.save() {
+            self.status = format!("Error: {}", e);
+        }
+        let case_dir = self.get_case_dir();
+        let key = case_dir.join(&self.c_cfg.source_code_path);
+        if self
+            .source_code_info
+            .as_ref()
+            .is_none_or(|(cached_key, _)| *cached_key != key)
+        {
+            let info = dependency::inspect_source_code(&case_dir, &self.c_cfg.source_code_path);
+            self.source_code_info = Some((key, info));
+        }
+        let Some((_, info)) = &self.source_code_info else {
+            return;
+        };
+        match info {
+            Ok(info) => {
+                ui.label(format!(
+                    "lbflow_soa {} found at {}",
+                    info.version,
+                    info.resolved_path.display()
+                ));
+                for warning in info.get_warnings(&self.c_cfg) {
+                    ui.colored_label(ui.visuals().warn_fg_color, warning);
+                }
+            }
+            Err(e) => {
+                ui.colored_label(ui.visuals().error_fg_color, e);
+            }
+        }
     }
 
     fn ui_par_mode(&mut self, ui: &mut egui::Ui) {
@@ -448,21 +809,57 @@ impl GuiApp {
             ui.label("Parent directory:");
             ui.text_edit_singleline(&mut self.parent_dir);
         });
+        ui.horizontal(|ui| {
+            ui.checkbox(
+                &mut self.c_cfg.workspace,
+                "Maintain a workspace Cargo.toml in the parent directory",
+            );
+            if ui.button("Update workspace").clicked() {
+                self.status =
+                    match writer::update_workspace(Path::new(&self.parent_dir), &self.c_cfg) {
+                        Ok(workspace_update) => {
+                            format!("Workspace updated, {}", workspace_update.get_summary())
+                        }
+                        Err(e) => format!("Error: {}", e),
+                    };
+            }
+        });
+    }
+
+    fn ui_tools_menu(&mut self, ui: &mut egui::Ui) {
+        ui.menu_button("Tools", |ui| {
+            self.ui_case_browser_menu_item(ui);
+            self.ui_compare_menu_item(ui);
+        });
+    }
+
+    fn ui_menu_bar(&mut self, ui: &mut egui::Ui) {
+        egui::MenuBar::new().ui(ui, |ui| {
+            self.ui_edit_menu(ui);
+            self.ui_templates_menu(ui);
+            self.ui_tools_menu(ui);
+        });
     }
 
     fn ui_case_informations(&mut self, ui: &mut egui::Ui) {
         ui.heading("Case informations");
+        if !self.template_note.is_empty() {
+            ui.label(&self.template_note);
+            ui.add_space(10.0);
+        }
         self.ui_case_name(ui);
         ui.add_space(10.0);
         self.ui_parent_dir(ui);
         ui.add_space(10.0);
-        self.ui_source_code_path(ui);
+        self.ui_dependency_source(ui);
         ui.add_space(10.0);
         self.ui_par_mode(ui);
+        ui.add_space(10.0);
+        self.ui_release_profile(ui);
     }
 
     fn ui_scalars(&mut self, ui: &mut egui::Ui) {
-        let dim = self.d_cfg.dim;
+        let (grid, dim) = (self.d_cfg.grid, self.d_cfg.dim);
         ui.heading("Scalars");
         ui.horizontal(|ui| {
             ui.label("Number of scalars:");
@@ -479,6 +876,10 @@ impl GuiApp {
         while self.s_cfg_vec.len() > self.n_scalars {
             self.s_cfg_vec.pop();
         }
+        if !self.s_cfg_vec.is_empty() {
+            self.ui_scalar_bulk_actions(ui);
+            ui.add_space(10.0);
+        }
         // for (i, _) in self.ps_cfg_vec.iter_mut().enumerate() {
         //     ui.collapsing(format!("Passive scalar {}", i), |ui| {
         //     ui.horizontal_wrapped(|ui| {
@@ -486,6 +887,7 @@ impl GuiApp {
         //         ui.label("It's a passive scalar.");
         //     });
         // });
+        let patch_error = self.get_patch_error();
         for (i, ps_cfg) in self.s_cfg_vec.iter_mut().enumerate() {
             let id = egui::Id::new(format!("ps_window_open_{}", i));
             let mut open = ui.memory(|mem| mem.data.get_temp::<bool>(id).unwrap_or(false));
@@ -509,7 +911,11 @@ impl GuiApp {
                         ui.separator();
                         ps_cfg.ui_initial_scalar_value(ui);
                         ui.separator();
-                        ps_cfg.ui_boundary_conditions(ui, dim);
+                        ps_cfg.ui_boundary_conditions(ui, grid, dim);
+                        if let Some(e) = &patch_error {
+                            ui.colored_label(ui.visuals().error_fg_color, e)
+                                .on_hover_text(PATCH_LIMITATION_NOTE);
+                        }
                         ui.add_space(10.0);
                         ps_cfg.ui_inner_boundary_condition(ui);
                     });
@@ -519,28 +925,64 @@ impl GuiApp {
         }
     }
 
+    fn ui_scalar_bulk_actions(&mut self, ui: &mut egui::Ui) {
+        if ui
+            .button("Scalar defaults from momentum walls")
+            .on_hover_text(
+                "No-flux (ABB) on walls, zeroth-order no-flux on velocity outlets and on the pressure faces with the lowest density, periodic where the flow is. Inflow faces keep their condition",
+            )
+            .clicked()
+        {
+            let mut inflow_faces = vec![];
+            for s_cfg in &mut self.s_cfg_vec {
+                inflow_faces = s_cfg.map_momentum_boundary_conditions(&self.m_cfg.boundary_conditions);
+            }
+            self.status = if inflow_faces.is_empty() {
+                "Scalar boundary conditions set from the momentum ones".to_string()
+            } else {
+                format!(
+                    "Scalar boundary conditions set from the momentum ones, except on the inflow faces: {}",
+                    inflow_faces
+                        .iter()
+                        .map(|boundary_face| boundary_face.to_literal())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            };
+        }
+        if self.s_cfg_vec.len() < 2 {
+            return;
+        }
+        let id = egui::Id::new("copy_boundary_conditions_source");
+        let mut source = ui
+            .memory(|mem| mem.data.get_temp::<usize>(id).unwrap_or(0))
+            .min(self.s_cfg_vec.len() - 1);
+        ui.horizontal(|ui| {
+            ui.label("Copy boundary conditions of");
+            egui::ComboBox::from_id_salt("copy_boundary_conditions_source_combo_box")
+                .selected_text(format!("{}: {}", source, self.s_cfg_vec[source].name))
+                .show_ui(ui, |ui| {
+                    for (i, s_cfg) in self.s_cfg_vec.iter().enumerate() {
+                        ui.selectable_value(&mut source, i, format!("{}: {}", i, s_cfg.name));
+                    }
+                });
+            if ui.button("to the other scalars").clicked() {
+                let boundary_conditions = self.s_cfg_vec[source].boundary_conditions.clone();
+                for s_cfg in &mut self.s_cfg_vec {
+                    s_cfg.boundary_conditions = boundary_conditions.clone();
+                }
+            }
+        });
+        ui.memory_mut(|mem| mem.data.insert_temp(id, source));
+    }
+
     fn ui_build_button(&mut self, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
             if ui.button("Build case").clicked() {
                 if self.c_cfg.case_name.trim().is_empty() {
                     self.status = "Case name empty".to_string();
                 } else {
-                    let result = (|| {
-                        let case_dir = PathBuf::from(&self.parent_dir).join(&self.c_cfg.case_name);
-                        let src_dir = case_dir.join("src");
-                        let pre_processing_dir = case_dir.join("pre_processing");
-                        std::fs::create_dir_all(&src_dir)?;
-                        std::fs::create_dir_all(&pre_processing_dir)?;
-                        let cargo_toml_content = self.c_cfg.get_cargo_toml();
-                        std::fs::write(case_dir.join("Cargo.toml"), cargo_toml_content)?;
-                        let main_rs_content = self.get_main_rs_content();
-                        std::fs::write(src_dir.join("main.rs"), main_rs_content)?;
-                        Ok::<(), std::io::Error>(())
-                    })();
-                    match result {
-                        Ok(_) => self.status = "Case created successfully".to_string(),
-                        Err(e) => self.status = format!("Error: {}", e),
-                    }
+                    self.build_case();
                 }
             }
             ui.label(&self.status);
@@ -549,49 +991,89 @@ impl GuiApp {
 }
 
 impl GuiApp {
-    fn get_s_params_vec_content(&self) -> String {
-        let mut s_params_contents = vec![];
-        for s_cfg in &self.s_cfg_vec {
-            let s_params_content = s_cfg.get_s_params_content();
-            s_params_contents.push(s_params_content);
+    fn get_case_dir(&self) -> PathBuf {
+        PathBuf::from(&self.parent_dir).join(&self.c_cfg.case_name)
+    }
+}
+
+fn ui_m_boundary_condition(
+    ui: &mut egui::Ui,
+    id_salt: String,
+    boundary_condition: &mut momentum::BoundaryConditionGui,
+    dim: Dimensionality,
+) {
+    let (cur_bb_rho, cur_ux, cur_uy, cur_uz) = match boundary_condition {
+        momentum::BoundaryConditionGui::BounceBack { rho, ux, uy, uz } => (*rho, *ux, *uy, *uz),
+        _ => (1.0, 0.0, 0.0, 0.0),
+    };
+    let cur_abb_rho = match boundary_condition {
+        momentum::BoundaryConditionGui::AntiBounceBack { rho } => *rho,
+        _ => 1.0,
+    };
+    egui::ComboBox::from_id_salt(id_salt)
+        .selected_text(boundary_condition.get_label())
+        .show_ui(ui, |ui| {
+            ui.selectable_value(
+                boundary_condition,
+                momentum::BoundaryConditionGui::NoSlip,
+                "No-slip",
+            );
+            ui.selectable_value(
+                boundary_condition,
+                momentum::BoundaryConditionGui::BounceBack {
+                    rho: cur_bb_rho,
+                    ux: cur_ux,
+                    uy: cur_uy,
+                    uz: cur_uz,
+                },
+                "Bounce-back",
+            );
+            ui.selectable_value(
+                boundary_condition,
+                momentum::BoundaryConditionGui::AntiBounceBack { rho: cur_abb_rho },
+                "Anti-bounce-back",
+            );
+            ui.selectable_value(
+                boundary_condition,
+                momentum::BoundaryConditionGui::Periodic,
+                "Periodic",
+            );
+        });
+    match boundary_condition {
+        momentum::BoundaryConditionGui::BounceBack { rho, ux, uy, uz } => {
+            ui.label("rho:");
+            ui.add(egui::DragValue::new(rho).speed(0.01));
+            ui.label("ux:");
+            ui.add(egui::DragValue::new(ux).speed(0.01));
+            ui.label("uy:");
+            ui.add(egui::DragValue::new(uy).speed(0.01));
+            if dim == Dimensionality::D3 {
+                ui.label("uz:");
+                ui.add(egui::DragValue::new(uz).speed(0.01));
+            } else {
+                *uz = 0.0;
+            }
+        }
+        momentum::BoundaryConditionGui::AntiBounceBack { rho } => {
+            ui.label("rho:");
+            ui.add(egui::DragValue::new(rho).speed(0.01));
         }
-        s_params_contents.join("\n")
-    }
-
-    fn get_solve_content(&self) -> String {
-        let s_params_vec_literal = self
-            .s_cfg_vec
-            .iter()
-            .map(|ps_cfg| ps_cfg.get_s_params_name_literal())
-            .collect::<Vec<String>>()
-            .join(", ");
-        format!(
-            "    solve(domain, m_params, vec![{}], vec![]);",
-            s_params_vec_literal
-        )
-    }
-
-    fn get_main_rs_content(&self) -> String {
-        let domain_content = self.d_cfg.get_domain_content();
-        let m_params_content = self.m_cfg.get_m_params_content(&self.d_cfg.dim);
-        let s_params_vec_content = self.get_s_params_vec_content();
-        let solve_content = self.get_solve_content();
-        format!(
-            r#"use lbflow_soa::prelude::*;
-
-fn main() {{
-{domain_content}
-{m_params_content}
-{s_params_vec_content}
-{solve_content}
-}}
-"#
-        )
+        _ => {}
     }
 }
 
 impl eframe::App for GuiApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.handle_history_shortcuts(ctx);
+        egui::TopBottomPanel::top("menu_bar_panel").show(ctx, |ui| {
+            self.ui_menu_bar(ui);
+        });
+        self.ui_template_windows(ctx);
+        self.ui_pending_build_window(ctx);
+        self.ui_compare_window(ctx);
+        self.ui_case_browser(ctx);
+        self.ui_restore_session_window(ctx);
+        self.ui_close_dialog(ctx);
         egui::CentralPanel::default().show(ctx, |ui| {
             egui::ScrollArea::vertical().show(ui, |ui| {
                 self.ui_case_informations(ui);
@@ -612,12 +1094,32 @@ impl eframe::App for GuiApp {
                 self.ui_m_boundary_conditions(ui);
                 ui.separator();
 
+                self.ui_domain_preview(ui);
+                ui.separator();
+
+                self.ui_geometry_analysis(ui);
+                ui.separator();
+
+                self.ui_estimator(ui);
+                ui.separator();
+
                 self.ui_scalars(ui);
                 ui.separator();
 
+                self.ui_job_script(ui);
+                ui.separator();
+
                 ui.add_space(10.0);
                 self.ui_build_button(ui);
+                ui.separator();
+
+                self.ui_run(ui);
             });
         });
+        self.record_history(ctx);
+    }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        self.save_session(storage);
     }
 }
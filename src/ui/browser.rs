@@ -0,0 +1,244 @@
+use eframe::egui;
+use std::path::Path;
+
+use super::GuiApp;
+use crate::cases::{self, CaseEntry};
+use crate::config::*;
+use crate::naming::NamingScheme;
+use crate::writer;
+
+#[derive(Default)]
+pub(crate) struct CaseBrowserGui {
+    open: bool,
+    entries: Vec<CaseEntry>,
+    duplicate: Option<(String, String)>,
+    pending_delete: Option<String>,
+    status: String,
+}
+
+enum CaseAction {
+    Open(usize),
+    Duplicate(String),
+    Delete(String),
+}
+
+fn get_entry_summary(entry: &CaseEntry) -> String {
+    let case = match &entry.case {
+        Ok(case) => case,
+        Err(_) => return "No case.json".to_string(),
+    };
+    let grid_literal = match case.d_cfg.dim {
+        Dimensionality::D2 => format!("2D {} x {}", case.d_cfg.grid[0], case.d_cfg.grid[1]),
+        Dimensionality::D3 => format!(
+            "3D {} x {} x {}",
+            case.d_cfg.grid[0], case.d_cfg.grid[1], case.d_cfg.grid[2]
+        ),
+    };
+    let scalar_names = case
+        .s_cfg_vec
+        .iter()
+        .map(|s_cfg| s_cfg.name.clone())
+        .collect::<Vec<String>>();
+    let mut summary = format!("{}, {}", grid_literal, case.m_cfg.velocity_set.get_label());
+    if !scalar_names.is_empty() {
+        summary.push_str(&format!(", scalars: {}", scalar_names.join(", ")));
+    }
+    summary
+}
+
+impl GuiApp {
+    fn refresh_case_browser(&mut self) {
+        match cases::scan_cases(Path::new(&self.parent_dir)) {
+            Ok(entries) => {
+                self.case_browser.entries = entries;
+                self.case_browser.status = String::new();
+            }
+            Err(e) => {
+                self.case_browser.entries = vec![];
+                self.case_browser.status = format!("Error: {}", e);
+            }
+        }
+    }
+
+    /// Keeps the workspace members in line with the cases on disk.
+    fn update_workspace_after_change(&mut self) {
+        if self.c_cfg.workspace
+            && let Err(e) = writer::update_workspace(Path::new(&self.parent_dir), &self.c_cfg)
+        {
+            self.case_browser.status = format!("Error: {}", e);
+        }
+    }
+
+    pub(super) fn ui_case_browser_menu_item(&mut self, ui: &mut egui::Ui) {
+        if ui
+            .checkbox(&mut self.case_browser.open, "Case browser")
+            .changed()
+            && self.case_browser.open
+        {
+            self.refresh_case_browser();
+        }
+    }
+
+    fn ui_case_entry(&self, ui: &mut egui::Ui, i: usize, entry: &CaseEntry) -> Option<CaseAction> {
+        let mut action = None;
+        ui.strong(&entry.name);
+        ui.label(get_entry_summary(entry));
+        if let Some(modified) = entry.modified {
+            let modified = chrono::DateTime::<chrono::Local>::from(modified);
+            ui.weak(format!("Modified {}", modified.format("%Y-%m-%d %H:%M")));
+        }
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(entry.case.is_ok(), egui::Button::new("Open"))
+                .clicked()
+            {
+                action = Some(CaseAction::Open(i));
+            }
+            if ui
+                .add_enabled(entry.case.is_ok(), egui::Button::new("Duplicate"))
+                .clicked()
+            {
+                action = Some(CaseAction::Duplicate(entry.name.clone()));
+            }
+            if ui
+                .add_enabled(entry.case.is_ok(), egui::Button::new("Delete"))
+                .clicked()
+            {
+                action = Some(CaseAction::Delete(entry.name.clone()));
+            }
+        });
+        action
+    }
+
+    pub(super) fn ui_case_browser(&mut self, ctx: &egui::Context) {
+        if !self.case_browser.open {
+            return;
+        }
+        let mut action = None;
+        egui::SidePanel::left("case_browser_panel")
+            .default_width(300.0)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.heading("Cases");
+                    if ui.button("Refresh").clicked() {
+                        self.refresh_case_browser();
+                    }
+                });
+                ui.label(&self.parent_dir);
+                ui.label(&self.case_browser.status);
+                ui.separator();
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for (i, entry) in self.case_browser.entries.iter().enumerate() {
+                        if let Some(entry_action) = self.ui_case_entry(ui, i, entry) {
+                            action = Some(entry_action);
+                        }
+                        ui.separator();
+                    }
+                });
+            });
+        match action {
+            Some(CaseAction::Open(i)) => {
+                if let Some(Ok(case)) = self.case_browser.entries.get(i).map(|e| &e.case) {
+                    let case = case.clone();
+                    self.apply_template(case);
+                    self.update_source_code_path();
+                    self.mark_saved();
+                    self.status = format!("Case {} opened", self.c_cfg.case_name);
+                }
+            }
+            Some(CaseAction::Duplicate(name)) => {
+                let new_name = match NamingScheme::parse(&self.settings.case_name_pattern) {
+                    Ok(naming_scheme) if naming_scheme.parse_name(&name).is_some() => {
+                        naming_scheme.get_next_variant(Path::new(&self.parent_dir), &name)
+                    }
+                    _ => format!("{}_copy", name),
+                };
+                self.case_browser.duplicate = Some((name, new_name));
+            }
+            Some(CaseAction::Delete(name)) => self.case_browser.pending_delete = Some(name),
+            None => {}
+        }
+        self.ui_duplicate_case_window(ctx);
+        self.ui_delete_case_window(ctx);
+    }
+
+    fn ui_duplicate_case_window(&mut self, ctx: &egui::Context) {
+        let Some((name, new_name)) = &mut self.case_browser.duplicate else {
+            return;
+        };
+        let mut confirmed = None;
+        egui::Window::new("Duplicate case")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(format!("New name for a copy of {}:", name));
+                    ui.text_edit_singleline(new_name);
+                });
+                ui.horizontal(|ui| {
+                    if ui.button("Duplicate").clicked() {
+                        confirmed = Some(true);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        confirmed = Some(false);
+                    }
+                });
+            });
+        match confirmed {
+            Some(true) => {
+                let Some((name, new_name)) = self.case_browser.duplicate.take() else {
+                    return;
+                };
+                let result = cases::duplicate_case(Path::new(&self.parent_dir), &name, &new_name);
+                self.refresh_case_browser();
+                match result {
+                    Ok(_) => {
+                        self.update_workspace_after_change();
+                    }
+                    Err(e) => self.case_browser.status = format!("Error: {}", e),
+                }
+            }
+            Some(false) => self.case_browser.duplicate = None,
+            None => {}
+        }
+    }
+
+    fn ui_delete_case_window(&mut self, ctx: &egui::Context) {
+        let Some(name) = &self.case_browser.pending_delete else {
+            return;
+        };
+        let mut confirmed = None;
+        egui::Window::new("Delete case")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Delete {} and everything in it, results included?",
+                    Path::new(&self.parent_dir).join(name).display()
+                ));
+                ui.horizontal(|ui| {
+                    if ui.button("Delete").clicked() {
+                        confirmed = Some(true);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        confirmed = Some(false);
+                    }
+                });
+            });
+        match confirmed {
+            Some(true) => {
+                let Some(name) = self.case_browser.pending_delete.take() else {
+                    return;
+                };
+                let result = cases::delete_case(Path::new(&self.parent_dir), &name);
+                self.refresh_case_browser();
+                match result {
+                    Ok(_) => self.update_workspace_after_change(),
+                    Err(e) => self.case_browser.status = format!("Error: {}", e),
+                }
+            }
+            Some(false) => self.case_browser.pending_delete = None,
+            None => {}
+        }
+    }
+}
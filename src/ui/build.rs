@@ -0,0 +1,212 @@
+use eframe::egui;
+use std::path::{Path, PathBuf};
+
+use super::GuiApp;
+use crate::naming::NamingScheme;
+use crate::writer::{self, FileStatus};
+
+pub(crate) struct PendingBuild {
+    case_dir: PathBuf,
+    files: Vec<(String, String)>,
+    statuses: Vec<(String, FileStatus)>,
+    /// Diff of each modified file, by path.
+    diffs: Vec<(String, String)>,
+    new_case_name: String,
+}
+
+enum PendingBuildAction {
+    Overwrite,
+    BackupAndOverwrite,
+    WriteToNewName,
+    Cancel,
+}
+
+impl GuiApp {
+    fn get_free_case_name(&self) -> String {
+        let parent_dir = PathBuf::from(&self.parent_dir);
+        if let Ok(naming_scheme) = NamingScheme::parse(&self.settings.case_name_pattern)
+            && naming_scheme.parse_name(&self.c_cfg.case_name).is_some()
+        {
+            return naming_scheme.get_next_variant(&parent_dir, &self.c_cfg.case_name);
+        }
+        (1..)
+            .map(|i| format!("{}_{}", self.c_cfg.case_name, i))
+            .find(|case_name| !parent_dir.join(case_name).exists())
+            .unwrap_or_default()
+    }
+
+    pub(super) fn build_case(&mut self) {
+        let files = match self.get_case_template().get_case_files() {
+            Ok(files) => files,
+            Err(e) => {
+                self.status = format!("Error: {}", e);
+                return;
+            }
+        };
+        let case_dir = self.get_case_dir();
+        let statuses = writer::compare_with_existing(&case_dir, &files);
+        if statuses
+            .iter()
+            .any(|(_, status)| matches!(status, FileStatus::Modified))
+        {
+            self.status = "Case directory already exists".to_string();
+            let diffs = files
+                .iter()
+                .zip(&statuses)
+                .filter(|(_, (_, status))| matches!(status, FileStatus::Modified))
+                .map(|((file_path, content), _)| {
+                    let diff = writer::get_diff(&case_dir, file_path, content);
+                    (file_path.clone(), diff)
+                })
+                .collect();
+            self.pending_build = Some(PendingBuild {
+                case_dir,
+                files,
+                statuses,
+                diffs,
+                new_case_name: self.get_free_case_name(),
+            });
+            return;
+        }
+        self.write_case(&case_dir, &files);
+    }
+
+    fn write_case(&mut self, case_dir: &Path, files: &[(String, String)]) {
+        let result = std::fs::create_dir_all(case_dir.join("pre_processing"))
+            .and_then(|_| writer::write_case_files(case_dir, files));
+        match result {
+            Ok(_) => {
+                self.status = "Case created successfully".to_string();
+                self.mark_saved();
+            }
+            Err(e) => {
+                self.status = format!("Error: {}", e);
+                return;
+            }
+        }
+        if self.c_cfg.workspace {
+            match writer::update_workspace(Path::new(&self.parent_dir), &self.c_cfg) {
+                Ok(workspace_update) => {
+                    self.status = format!(
+                        "Case created successfully, {}",
+                        workspace_update.get_summary()
+                    )
+                }
+                Err(e) => self.status = format!("Error: {}", e),
+            }
+        }
+    }
+
+    pub(super) fn ui_pending_build_window(&mut self, ctx: &egui::Context) {
+        let Some(pending_build) = &mut self.pending_build else {
+            return;
+        };
+        let mut action = None;
+        let mut open = true;
+        egui::Window::new("Case directory already exists")
+            .open(&mut open)
+            .default_width(600.0)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "Building would change files in {}:",
+                    pending_build.case_dir.display()
+                ));
+                egui::ScrollArea::vertical()
+                    .max_height(400.0)
+                    .show(ui, |ui| {
+                        for (file_path, status) in &pending_build.statuses {
+                            match status {
+                                FileStatus::New => {
+                                    ui.label(format!("{} (new)", file_path));
+                                }
+                                FileStatus::Unchanged => {
+                                    ui.weak(format!("{} (unchanged)", file_path));
+                                }
+                                FileStatus::Modified => {
+                                    ui.collapsing(format!("{} (modified)", file_path), |ui| {
+                                        if let Some((_, diff)) = pending_build
+                                            .diffs
+                                            .iter()
+                                            .find(|(diff_path, _)| diff_path == file_path)
+                                        {
+                                            ui_diff(ui, diff);
+                                        }
+                                    });
+                                }
+                            }
+                        }
+                    });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Overwrite").clicked() {
+                        action = Some(PendingBuildAction::Overwrite);
+                    }
+                    if ui.button("Back up and overwrite").clicked() {
+                        action = Some(PendingBuildAction::BackupAndOverwrite);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        action = Some(PendingBuildAction::Cancel);
+                    }
+                });
+                ui.horizontal(|ui| {
+                    if ui.button("Write to new name:").clicked() {
+                        action = Some(PendingBuildAction::WriteToNewName);
+                    }
+                    ui.text_edit_singleline(&mut pending_build.new_case_name);
+                });
+            });
+        if !open {
+            action = Some(PendingBuildAction::Cancel);
+        }
+        let Some(action) = action else {
+            return;
+        };
+        let Some(pending_build) = self.pending_build.take() else {
+            return;
+        };
+        match action {
+            PendingBuildAction::Overwrite => {
+                self.write_case(&pending_build.case_dir, &pending_build.files);
+            }
+            PendingBuildAction::BackupAndOverwrite => {
+                match writer::backup_existing_files(&pending_build.case_dir, &pending_build.files) {
+                    Ok(backup_dir) => {
+                        self.write_case(&pending_build.case_dir, &pending_build.files);
+                        self.status = format!(
+                            "{} (previous files in {})",
+                            self.status,
+                            backup_dir.display()
+                        );
+                    }
+                    Err(e) => self.status = format!("Error: {}", e),
+                }
+            }
+            PendingBuildAction::WriteToNewName => {
+                let new_case_name = pending_build.new_case_name.trim().to_string();
+                if new_case_name.is_empty() {
+                    self.status = "Case name empty".to_string();
+                    self.pending_build = Some(pending_build);
+                } else {
+                    self.c_cfg.case_name = new_case_name;
+                    self.build_case();
+                }
+            }
+            PendingBuildAction::Cancel => {
+                self.status = "Build cancelled".to_string();
+            }
+        }
+    }
+}
+
+fn ui_diff(ui: &mut egui::Ui, diff: &str) {
+    for line in diff.lines() {
+        let text = egui::RichText::new(line).monospace();
+        if line.starts_with('+') && !line.starts_with("+++") {
+            ui.colored_label(egui::Color32::from_rgb(60, 160, 60), text);
+        } else if line.starts_with('-') && !line.starts_with("---") {
+            ui.colored_label(egui::Color32::from_rgb(200, 60, 60), text);
+        } else {
+            ui.label(text);
+        }
+    }
+}
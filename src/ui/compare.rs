@@ -0,0 +1,98 @@
+use eframe::egui;
+use std::path::Path;
+
+use super::GuiApp;
+use crate::compare::{self, FieldDiff};
+
+#[derive(Default)]
+pub(crate) struct CompareGui {
+    open: bool,
+    left_path: String,
+    right_path: String,
+    diffs: Vec<FieldDiff>,
+    status: String,
+}
+
+impl GuiApp {
+    /// Compares two cases, the current configuration standing in for an
+    /// empty left path.
+    fn run_comparison(&self) -> Result<Vec<FieldDiff>, String> {
+        let left_path = self.compare.left_path.trim();
+        let left = if left_path.is_empty() {
+            self.get_case_template()
+        } else {
+            compare::load_case(Path::new(left_path))?
+        };
+        let right = compare::load_case(Path::new(self.compare.right_path.trim()))?;
+        compare::compare_cases(&left, &right)
+    }
+
+    pub(super) fn ui_compare_menu_item(&mut self, ui: &mut egui::Ui) {
+        if ui.button("Compare cases...").clicked() {
+            self.compare.open = true;
+        }
+    }
+
+    pub(super) fn ui_compare_window(&mut self, ctx: &egui::Context) {
+        let mut open = self.compare.open;
+        egui::Window::new("Compare cases")
+            .open(&mut open)
+            .default_width(700.0)
+            .show(ctx, |ui| {
+                ui.label("Case directories, case.json files or saved templates.");
+                egui::Grid::new("compare_paths").show(ui, |ui| {
+                    ui.label("Left:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.compare.left_path)
+                            .hint_text("<current configuration>")
+                            .desired_width(500.0),
+                    );
+                    ui.end_row();
+                    ui.label("Right:");
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.compare.right_path)
+                            .desired_width(500.0),
+                    );
+                    ui.end_row();
+                });
+                ui.horizontal(|ui| {
+                    if ui.button("Compare").clicked() {
+                        match self.run_comparison() {
+                            Ok(diffs) => {
+                                self.compare.status = format!("{} difference(s)", diffs.len());
+                                self.compare.diffs = diffs;
+                            }
+                            Err(e) => {
+                                self.compare.diffs = vec![];
+                                self.compare.status = format!("Error: {}", e);
+                            }
+                        }
+                    }
+                    ui.label(&self.compare.status);
+                });
+                if self.compare.diffs.is_empty() {
+                    return;
+                }
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .max_height(500.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("compare_diffs")
+                            .striped(true)
+                            .show(ui, |ui| {
+                                ui.strong("Field");
+                                ui.strong("Left");
+                                ui.strong("Right");
+                                ui.end_row();
+                                for diff in &self.compare.diffs {
+                                    ui.label(&diff.field);
+                                    ui.label(diff.left.as_deref().unwrap_or("-"));
+                                    ui.label(diff.right.as_deref().unwrap_or("-"));
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            });
+        self.compare.open = open;
+    }
+}
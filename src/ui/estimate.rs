@@ -0,0 +1,107 @@
+use eframe::egui;
+
+use super::GuiApp;
+use crate::config::VelocitySetGui;
+use crate::estimate::{self, MemoryEstimate};
+
+pub(crate) struct EstimatorGui {
+    pub(super) n_steps: u64,
+    mlups: f64,
+    available_memory: Option<u64>,
+}
+
+impl Default for EstimatorGui {
+    fn default() -> Self {
+        EstimatorGui {
+            n_steps: 10000,
+            mlups: 100.0,
+            available_memory: estimate::get_available_memory(),
+        }
+    }
+}
+
+impl GuiApp {
+    pub(super) fn get_memory_estimate(&self) -> MemoryEstimate {
+        let s_velocity_sets = self
+            .s_cfg_vec
+            .iter()
+            .map(|s_cfg| s_cfg.velocity_set.clone())
+            .collect::<Vec<VelocitySetGui>>();
+        estimate::estimate_memory(
+            self.d_cfg.grid,
+            self.d_cfg.dim,
+            &self.m_cfg.velocity_set,
+            &s_velocity_sets,
+        )
+    }
+
+    pub(super) fn ui_estimator(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Memory and runtime estimate");
+        let memory = self.get_memory_estimate();
+        ui.label(format!(
+            "{} nodes: populations {}, macroscopic fields {}, total {}",
+            memory.n_nodes,
+            estimate::get_bytes_literal(memory.population_bytes),
+            estimate::get_bytes_literal(memory.macroscopic_bytes),
+            estimate::get_bytes_literal(memory.get_total_bytes())
+        ))
+        .on_hover_text("f64 storage, two copies of the populations");
+        match self.estimator.available_memory {
+            Some(available_memory) if memory.get_total_bytes() > available_memory => {
+                ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    format!(
+                        "Does not fit in the available memory ({})",
+                        estimate::get_bytes_literal(available_memory)
+                    ),
+                );
+            }
+            Some(available_memory) => {
+                ui.label(format!(
+                    "Available memory: {} ({:.0} % used)",
+                    estimate::get_bytes_literal(available_memory),
+                    100.0 * memory.get_total_bytes() as f64 / available_memory as f64
+                ));
+            }
+            None => {
+                ui.label("Available memory unknown");
+            }
+        }
+        let last_run = self.get_last_run();
+        let estimator = &mut self.estimator;
+        ui.horizontal(|ui| {
+            ui.label("Time steps:");
+            ui.add(egui::DragValue::new(&mut estimator.n_steps).speed(100));
+            ui.label("MLUPS:");
+            ui.add(
+                egui::DragValue::new(&mut estimator.mlups)
+                    .range(0.001..=f64::MAX)
+                    .speed(1.0),
+            );
+            if ui
+                .add_enabled(
+                    last_run.is_some(),
+                    egui::Button::new("Measure from last run"),
+                )
+                .on_hover_text(
+                    "Uses the duration of the case executable in the last run, without the \
+                     compilation, and the nodes and time steps when that run started",
+                )
+                .clicked()
+                && let Some((timed_run, elapsed)) = last_run
+                && let Some(mlups) =
+                    estimate::measure_mlups(timed_run.n_nodes, timed_run.n_steps, elapsed)
+            {
+                estimator.mlups = mlups;
+            }
+        });
+        if let Some(wall_time) =
+            estimate::estimate_wall_time(memory.n_nodes, estimator.n_steps, estimator.mlups)
+        {
+            ui.label(format!(
+                "Estimated wall time: {}",
+                estimate::get_duration_literal(wall_time)
+            ));
+        }
+    }
+}
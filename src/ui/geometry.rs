@@ -0,0 +1,160 @@
+use eframe::egui;
+use std::path::PathBuf;
+
+use super::GuiApp;
+use crate::config::*;
+use crate::geometry::{self, AxisConnectionKind, NodeTypeMap, PercolationReport};
+
+#[derive(Default)]
+pub(crate) struct GeometryAnalysisGui {
+    map_file_path: String,
+    report: Option<PercolationReport>,
+    status: String,
+}
+
+impl GuiApp {
+    fn get_map_file_path(&self) -> PathBuf {
+        let map_file_path = &self.geometry_analysis.map_file_path;
+        if map_file_path.trim().is_empty() {
+            PathBuf::from(&self.parent_dir)
+                .join(&self.c_cfg.case_name)
+                .join(domain::MAP_FILE_PATH)
+        } else {
+            PathBuf::from(map_file_path)
+        }
+    }
+
+    pub(super) fn load_node_type_map(&self) -> Result<NodeTypeMap, String> {
+        match self.d_cfg.get_node_type_map(self.m_cfg.delta_x) {
+            Some(map) => {
+                let mut map = map?;
+                let patches = self
+                    .m_cfg
+                    .boundary_patches
+                    .iter()
+                    .map(|patch_bc| &patch_bc.patch)
+                    .chain(self.s_cfg_vec.iter().flat_map(|s_cfg| {
+                        s_cfg
+                            .boundary_patches
+                            .iter()
+                            .map(|patch_bc| &patch_bc.patch)
+                    }))
+                    .collect::<Vec<&FacePatchGui>>();
+                map.close_faces_outside_patches(&patches, self.d_cfg.dim);
+                Ok(map)
+            }
+            None => NodeTypeMap::from_file(&self.get_map_file_path(), self.d_cfg.grid),
+        }
+    }
+
+    fn run_percolation_analysis(&self) -> Result<(NodeTypeMap, PercolationReport), String> {
+        let map = self.load_node_type_map()?;
+        let report = geometry::analyze_percolation(
+            &map,
+            self.d_cfg.dim,
+            &self.m_cfg.velocity_set,
+            &self.m_cfg.get_generated_boundary_conditions(),
+        );
+        Ok((map, report))
+    }
+
+    pub(super) fn ui_geometry_analysis(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Geometry analysis");
+        if self.d_cfg.node_type_mask == NodeTypeMaskGui::FromMapFile {
+            ui.horizontal(|ui| {
+                ui.label("Map file:");
+                ui.add(
+                    egui::TextEdit::singleline(&mut self.geometry_analysis.map_file_path)
+                        .hint_text("<case>/pre_processing/map.csv"),
+                );
+            });
+        }
+        ui.horizontal(|ui| {
+            if ui.button("Analyze connectivity").clicked() {
+                match self.run_percolation_analysis() {
+                    Ok((_, report)) => {
+                        self.geometry_analysis.report = Some(report);
+                        self.geometry_analysis.status = String::new();
+                    }
+                    Err(e) => {
+                        self.geometry_analysis.report = None;
+                        self.geometry_analysis.status = format!("Error: {}", e);
+                    }
+                }
+            }
+            let has_isolated_nodes = self
+                .geometry_analysis
+                .report
+                .as_ref()
+                .is_some_and(|report| report.n_isolated_nodes > 0);
+            let can_fill =
+                has_isolated_nodes && self.d_cfg.node_type_mask == NodeTypeMaskGui::FromMapFile;
+            if ui
+                .add_enabled(can_fill, egui::Button::new("Fill isolated pockets"))
+                .clicked()
+            {
+                let result = self
+                    .run_percolation_analysis()
+                    .and_then(|(mut map, report)| {
+                        report.fill_isolated_components(&mut map);
+                        let backup_path =
+                            map.write_solid_nodes_to_file(&self.get_map_file_path())?;
+                        Ok((report.n_isolated_nodes, backup_path))
+                    });
+                self.geometry_analysis.status = match result {
+                    Ok((n_filled, backup_path)) => format!(
+                        "{} nodes turned into solid, previous map saved to {}",
+                        n_filled,
+                        backup_path.display()
+                    ),
+                    Err(e) => format!("Error: {}", e),
+                };
+                self.geometry_analysis.report = self
+                    .run_percolation_analysis()
+                    .ok()
+                    .map(|(_, report)| report);
+            }
+            ui.label(&self.geometry_analysis.status);
+        });
+        if let Some(report) = &self.geometry_analysis.report {
+            ui.label(format!(
+                "Fluid nodes: {} in {} connected region(s)",
+                report.n_fluid_nodes,
+                report.component_sizes.len()
+            ));
+            if report.isolated_components.is_empty() {
+                ui.label("No isolated fluid pockets");
+            } else {
+                ui.colored_label(
+                    ui.visuals().warn_fg_color,
+                    format!(
+                        "{} isolated pocket(s) with {} nodes",
+                        report.isolated_components.len(),
+                        report.n_isolated_nodes
+                    ),
+                );
+            }
+            for axis_connection in &report.axis_connections {
+                let axis_literal = ["x", "y", "z"][axis_connection.axis];
+                let kind_literal = match axis_connection.kind {
+                    AxisConnectionKind::InflowOutflow => "inflow/outflow",
+                    AxisConnectionKind::Periodic => "periodic",
+                };
+                if axis_connection.connected {
+                    ui.label(format!(
+                        "Faces along {} ({}) are connected",
+                        axis_literal, kind_literal
+                    ));
+                } else {
+                    ui.colored_label(
+                        ui.visuals().error_fg_color,
+                        format!(
+                            "Faces along {} ({}) are NOT connected",
+                            axis_literal, kind_literal
+                        ),
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use eframe::egui;
+
+use super::GuiApp;
+use crate::templates::CaseTemplate;
+
+const MAX_HISTORY_LENGTH: usize = 200;
+
+/// Edit history of the case configuration, stored as JSON snapshots.
+#[derive(Default)]
+pub(crate) struct HistoryGui {
+    undo_stack: Vec<String>,
+    redo_stack: Vec<String>,
+    current: Option<String>,
+}
+
+impl GuiApp {
+    pub(super) fn get_case_snapshot(&self) -> Option<String> {
+        serde_json::to_string(&self.get_case_template()).ok()
+    }
+
+    fn restore_case_snapshot(&mut self, snapshot: &str) {
+        if let Ok(case) = serde_json::from_str::<CaseTemplate>(snapshot) {
+            self.set_case_template(case);
+        }
+    }
+
+    pub(super) fn can_undo(&self) -> bool {
+        !self.history.undo_stack.is_empty()
+    }
+
+    pub(super) fn can_redo(&self) -> bool {
+        !self.history.redo_stack.is_empty()
+    }
+
+    pub(super) fn undo(&mut self) {
+        let Some(snapshot) = self.history.undo_stack.pop() else {
+            return;
+        };
+        if let Some(current) = self.history.current.replace(snapshot.clone()) {
+            self.history.redo_stack.push(current);
+        }
+        self.restore_case_snapshot(&snapshot);
+    }
+
+    pub(super) fn redo(&mut self) {
+        let Some(snapshot) = self.history.redo_stack.pop() else {
+            return;
+        };
+        if let Some(current) = self.history.current.replace(snapshot.clone()) {
+            self.history.undo_stack.push(current);
+        }
+        self.restore_case_snapshot(&snapshot);
+    }
+
+    /// Handles the undo and redo shortcuts, unless a text field has the
+    /// keyboard focus and handles them itself.
+    pub(super) fn handle_history_shortcuts(&mut self, ctx: &egui::Context) {
+        if ctx.memory(|memory| memory.focused().is_some()) {
+            return;
+        }
+        let redo_shortcut = egui::KeyboardShortcut::new(
+            egui::Modifiers::COMMAND | egui::Modifiers::SHIFT,
+            egui::Key::Z,
+        );
+        let undo_shortcut = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z);
+        if ctx.input_mut(|input| input.consume_shortcut(&redo_shortcut)) {
+            self.redo();
+        } else if ctx.input_mut(|input| input.consume_shortcut(&undo_shortcut)) {
+            self.undo();
+        }
+    }
+
+    /// Records the configuration as a new undo step once an edit is over:
+    /// drags are grouped until the pointer is released and typing until the
+    /// text field loses the focus.
+    pub(super) fn record_history(&mut self, ctx: &egui::Context) {
+        let Some(snapshot) = self.get_case_snapshot() else {
+            return;
+        };
+        let Some(current) = &self.history.current else {
+            self.history.current = Some(snapshot);
+            return;
+        };
+        if *current == snapshot {
+            return;
+        }
+        let is_editing = ctx.input(|input| input.pointer.any_down())
+            || ctx.memory(|memory| memory.focused().is_some());
+        if is_editing {
+            return;
+        }
+        let history = &mut self.history;
+        if let Some(current) = history.current.replace(snapshot) {
+            history.undo_stack.push(current);
+        }
+        if history.undo_stack.len() > MAX_HISTORY_LENGTH {
+            history.undo_stack.remove(0);
+        }
+        history.redo_stack.clear();
+    }
+
+    pub(super) fn ui_edit_menu(&mut self, ui: &mut egui::Ui) {
+        ui.menu_button("Edit", |ui| {
+            if ui
+                .add_enabled(
+                    self.can_undo(),
+                    egui::Button::new("Undo").shortcut_text("Ctrl+Z"),
+                )
+                .clicked()
+            {
+                self.undo();
+            }
+            if ui
+                .add_enabled(
+                    self.can_redo(),
+                    egui::Button::new("Redo").shortcut_text("Ctrl+Shift+Z"),
+                )
+                .clicked()
+            {
+                self.redo();
+            }
+        });
+    }
+}
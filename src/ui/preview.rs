@@ -0,0 +1,476 @@
+use eframe::egui::{self, Align2, Color32, FontId, Pos2, Rect, Stroke, Vec2};
+
+use super::GuiApp;
+use crate::config::*;
+use crate::geometry::NodeTypeMap;
+
+const MAX_PREVIEW_CELLS: usize = 200;
+const SOLID_COLOR: Color32 = Color32::from_rgb(90, 70, 60);
+const FLUID_COLOR: Color32 = Color32::from_rgb(200, 225, 245);
+const SLICE_COLOR: Color32 = Color32::from_rgb(230, 200, 40);
+const NOT_SET_COLOR: Color32 = Color32::from_rgb(200, 40, 40);
+
+pub(crate) struct DomainPreviewGui {
+    /// Index of the scalar whose boundary conditions colour the faces, or
+    /// `None` for the momentum ones.
+    coloured_scalar: Option<usize>,
+    slice_axis: usize,
+    slice_index: usize,
+    map: Option<NodeTypeMap>,
+    status: String,
+}
+
+impl Default for DomainPreviewGui {
+    fn default() -> Self {
+        DomainPreviewGui {
+            coloured_scalar: None,
+            slice_axis: 2,
+            slice_index: 0,
+            map: None,
+            status: String::new(),
+        }
+    }
+}
+
+fn get_momentum_face_color(boundary_condition: &momentum::BoundaryConditionGui) -> Color32 {
+    match boundary_condition {
+        momentum::BoundaryConditionGui::NoSlip => Color32::from_rgb(128, 128, 128),
+        momentum::BoundaryConditionGui::BounceBack { .. } => Color32::from_rgb(40, 110, 220),
+        momentum::BoundaryConditionGui::AntiBounceBack { .. } => Color32::from_rgb(230, 120, 30),
+        momentum::BoundaryConditionGui::Periodic => Color32::from_rgb(50, 170, 80),
+    }
+}
+
+/// Walls in grey, outflow no-flux conditions in blue and prescribed values
+/// in orange, as for the momentum conditions.
+fn get_scalar_face_color(boundary_condition: &scalar::BoundaryConditionGui) -> Color32 {
+    match boundary_condition {
+        scalar::BoundaryConditionGui::AntiBBNoFlux | scalar::BoundaryConditionGui::BBNoFlux => {
+            Color32::from_rgb(128, 128, 128)
+        }
+        scalar::BoundaryConditionGui::ZerothOrderNoFlux
+        | scalar::BoundaryConditionGui::SecondOrderNoFlux => Color32::from_rgb(40, 110, 220),
+        scalar::BoundaryConditionGui::AntiBounceBack { .. } => Color32::from_rgb(230, 120, 30),
+        scalar::BoundaryConditionGui::Periodic => Color32::from_rgb(50, 170, 80),
+    }
+}
+
+impl GuiApp {
+    fn get_coloured_scalar(&self) -> Option<&scalar::GuiConfig> {
+        self.domain_preview
+            .coloured_scalar
+            .and_then(|i| self.s_cfg_vec.get(i))
+    }
+
+    fn get_preview_face_color(&self, boundary_face: BoundaryFaceGui) -> Color32 {
+        let color = match self.get_coloured_scalar() {
+            Some(s_cfg) => s_cfg
+                .get_face_bc(boundary_face)
+                .map(|face_bc| get_scalar_face_color(&face_bc.boundary_condition)),
+            None => self
+                .m_cfg
+                .get_face_bc(boundary_face)
+                .map(|face_bc| get_momentum_face_color(&face_bc.boundary_condition)),
+        };
+        color.unwrap_or(NOT_SET_COLOR)
+    }
+
+    fn paint_slice(
+        &self,
+        painter: &egui::Painter,
+        rect: Rect,
+        (h_axis, v_axis): (usize, usize),
+        (fixed_axis, fixed_index): (usize, usize),
+    ) {
+        let grid = self.d_cfg.grid;
+        painter.rect_filled(rect, 0.0, FLUID_COLOR);
+        if let Some(map) = &self.domain_preview.map {
+            let n_h = grid[h_axis].min(MAX_PREVIEW_CELLS);
+            let n_v = grid[v_axis].min(MAX_PREVIEW_CELLS);
+            let cell = Vec2::new(rect.width() / n_h as f32, rect.height() / n_v as f32);
+            for j in 0..n_v {
+                let mut run_start = None;
+                for i in 0..=n_h {
+                    let is_solid = i < n_h && {
+                        let mut coords = [0_usize; 3];
+                        coords[h_axis] = i * grid[h_axis] / n_h;
+                        coords[v_axis] = j * grid[v_axis] / n_v;
+                        coords[fixed_axis] = fixed_index;
+                        map.is_solid(coords[0], coords[1], coords[2])
+                    };
+                    match (is_solid, run_start) {
+                        (true, None) => run_start = Some(i),
+                        (false, Some(start)) => {
+                            let min = Pos2::new(
+                                rect.left() + start as f32 * cell.x,
+                                rect.bottom() - (j + 1) as f32 * cell.y,
+                            );
+                            let size = Vec2::new((i - start) as f32 * cell.x, cell.y);
+                            painter.rect_filled(Rect::from_min_size(min, size), 0.0, SOLID_COLOR);
+                            run_start = None;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        let text_color = painter.ctx().style().visuals.text_color();
+        let font = FontId::proportional(13.0);
+        let edges = [
+            (
+                BoundaryFaceGui::from_axis(h_axis, true),
+                [rect.left_bottom(), rect.left_top()],
+                rect.left_center() - Vec2::new(6.0, 0.0),
+                Align2::RIGHT_CENTER,
+            ),
+            (
+                BoundaryFaceGui::from_axis(h_axis, false),
+                [rect.right_bottom(), rect.right_top()],
+                rect.right_center() + Vec2::new(6.0, 0.0),
+                Align2::LEFT_CENTER,
+            ),
+            (
+                BoundaryFaceGui::from_axis(v_axis, true),
+                [rect.left_bottom(), rect.right_bottom()],
+                rect.center_bottom() + Vec2::new(0.0, 6.0),
+                Align2::CENTER_TOP,
+            ),
+            (
+                BoundaryFaceGui::from_axis(v_axis, false),
+                [rect.left_top(), rect.right_top()],
+                rect.center_top() - Vec2::new(0.0, 6.0),
+                Align2::CENTER_BOTTOM,
+            ),
+        ];
+        for (boundary_face, points, label_pos, label_align) in edges {
+            let color = self.get_preview_face_color(boundary_face);
+            painter.line_segment(points, Stroke::new(4.0, color));
+            painter.text(
+                label_pos,
+                label_align,
+                boundary_face.to_literal(),
+                font.clone(),
+                color,
+            );
+        }
+
+        let origin = rect.left_bottom() + Vec2::new(-30.0, 30.0);
+        let axis_stroke = Stroke::new(1.5, text_color);
+        painter.arrow(origin, Vec2::new(30.0, 0.0), axis_stroke);
+        painter.arrow(origin, Vec2::new(0.0, -30.0), axis_stroke);
+        painter.text(
+            origin + Vec2::new(34.0, 0.0),
+            Align2::LEFT_CENTER,
+            AXIS_NAMES[h_axis],
+            font.clone(),
+            text_color,
+        );
+        painter.text(
+            origin + Vec2::new(0.0, -34.0),
+            Align2::CENTER_BOTTOM,
+            AXIS_NAMES[v_axis],
+            font,
+            text_color,
+        );
+    }
+
+    fn ui_preview_slice(&self, ui: &mut egui::Ui, axes: (usize, usize), fixed: (usize, usize)) {
+        let grid = self.d_cfg.grid;
+        let max_size = Vec2::new((ui.available_width().min(700.0) - 160.0).max(120.0), 320.0);
+        let aspect = grid[axes.0] as f32 / grid[axes.1] as f32;
+        let domain_size = if aspect * max_size.y > max_size.x {
+            Vec2::new(max_size.x, max_size.x / aspect)
+        } else {
+            Vec2::new(max_size.y * aspect, max_size.y)
+        };
+        let margin = Vec2::new(80.0, 40.0);
+        let (response, painter) =
+            ui.allocate_painter(domain_size + 2.0 * margin, egui::Sense::hover());
+        let rect = Rect::from_min_size(response.rect.min + margin, domain_size);
+        self.paint_slice(&painter, rect, axes, fixed);
+    }
+
+    fn ui_preview_isometric(&self, ui: &mut egui::Ui) {
+        let grid = self.d_cfg.grid;
+        let max_extent = grid.iter().copied().max().unwrap_or(1) as f32;
+        let extent = grid.map(|n| n as f32 / max_extent);
+        let (cos30, sin30) = (30_f32.to_radians().cos(), 30_f32.to_radians().sin());
+        let project = |p: [f32; 3]| Vec2::new((p[0] - p[1]) * cos30, (p[0] + p[1]) * sin30 - p[2]);
+
+        let size = Vec2::new(ui.available_width().min(500.0), 320.0);
+        let (response, painter) = ui.allocate_painter(size, egui::Sense::hover());
+        let corners = (0..8)
+            .map(|i| {
+                project([
+                    if i & 1 == 0 { 0.0 } else { extent[0] },
+                    if i & 2 == 0 { 0.0 } else { extent[1] },
+                    if i & 4 == 0 { 0.0 } else { extent[2] },
+                ])
+            })
+            .collect::<Vec<Vec2>>();
+        let (mut min, mut max) = (corners[0], corners[0]);
+        for corner in &corners {
+            min = min.min(*corner);
+            max = max.max(*corner);
+        }
+        let inner = response.rect.shrink(50.0);
+        let scale = (inner.width() / (max.x - min.x).max(1e-6))
+            .min(inner.height() / (max.y - min.y).max(1e-6));
+        let offset = inner.center().to_vec2() - 0.5 * (min + max) * scale;
+        let to_screen = |p: [f32; 3]| (project(p) * scale + offset).to_pos2();
+
+        let text_color = ui.visuals().text_color();
+        let font = FontId::proportional(13.0);
+        let faces = [
+            BoundaryFaceGui::West,
+            BoundaryFaceGui::South,
+            BoundaryFaceGui::Bottom,
+            BoundaryFaceGui::East,
+            BoundaryFaceGui::North,
+            BoundaryFaceGui::Top,
+        ];
+        for boundary_face in faces {
+            let axis = boundary_face.get_axis();
+            let (a, b) = ((axis + 1) % 3, (axis + 2) % 3);
+            let fixed = if boundary_face.is_min_side() {
+                0.0
+            } else {
+                extent[axis]
+            };
+            let points = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]
+                .map(|(s, t)| {
+                    let mut p = [0.0; 3];
+                    p[axis] = fixed;
+                    p[a] = s * extent[a];
+                    p[b] = t * extent[b];
+                    to_screen(p)
+                })
+                .to_vec();
+            let centroid = points.iter().fold(Vec2::ZERO, |sum, p| sum + p.to_vec2()) / 4.0;
+            let mut normal = [0.0; 3];
+            normal[axis] = if boundary_face.is_min_side() {
+                -1.0
+            } else {
+                1.0
+            };
+            let label_pos = centroid.to_pos2() + project(normal).normalized() * 20.0;
+            let color = self.get_preview_face_color(boundary_face);
+            let is_hidden = boundary_face.is_min_side();
+            let fill = color.gamma_multiply(if is_hidden { 0.15 } else { 0.45 });
+            painter.add(egui::Shape::convex_polygon(
+                points,
+                fill,
+                Stroke::new(1.5, color),
+            ));
+            painter.text(
+                label_pos,
+                Align2::CENTER_CENTER,
+                boundary_face.to_literal(),
+                font.clone(),
+                if is_hidden {
+                    color.gamma_multiply(0.6)
+                } else {
+                    color
+                },
+            );
+        }
+
+        let slice_axis = self.domain_preview.slice_axis;
+        let (a, b) = ((slice_axis + 1) % 3, (slice_axis + 2) % 3);
+        let last_index = grid[slice_axis].saturating_sub(1);
+        let slice_position = if last_index > 0 {
+            self.domain_preview.slice_index as f32 / last_index as f32
+        } else {
+            0.0
+        } * extent[slice_axis];
+        let slice_points = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0), (0.0, 0.0)]
+            .map(|(s, t)| {
+                let mut p = [0.0; 3];
+                p[slice_axis] = slice_position;
+                p[a] = s * extent[a];
+                p[b] = t * extent[b];
+                to_screen(p)
+            })
+            .to_vec();
+        painter.add(egui::Shape::line(
+            slice_points,
+            Stroke::new(2.0, SLICE_COLOR),
+        ));
+
+        let origin = to_screen([0.0, 0.0, 0.0]);
+        for (axis, axis_name) in AXIS_NAMES.iter().enumerate() {
+            let mut unit = [0.0; 3];
+            unit[axis] = 1.0;
+            let direction = project(unit).normalized() * 35.0;
+            painter.arrow(origin, direction, Stroke::new(1.5, text_color));
+            painter.text(
+                origin + direction * 1.2,
+                Align2::CENTER_CENTER,
+                *axis_name,
+                font.clone(),
+                text_color,
+            );
+        }
+    }
+
+    fn ui_preview_boundary_table(&self, ui: &mut egui::Ui) {
+        let faces = BoundaryFaceGui::get_faces(self.d_cfg.dim);
+        egui::Grid::new("domain_preview_boundary_grid")
+            .striped(true)
+            .show(ui, |ui| {
+                ui.strong("Face");
+                ui.strong("Momentum");
+                for s_cfg in &self.s_cfg_vec {
+                    ui.strong(&s_cfg.name);
+                }
+                ui.end_row();
+                for boundary_face in &faces {
+                    ui.colored_label(
+                        self.get_preview_face_color(*boundary_face),
+                        boundary_face.to_literal(),
+                    );
+                    ui.label(
+                        self.m_cfg
+                            .get_face_bc(*boundary_face)
+                            .map_or("Not set", |face_bc| face_bc.boundary_condition.get_label()),
+                    );
+                    for s_cfg in &self.s_cfg_vec {
+                        ui.label(
+                            s_cfg
+                                .get_face_bc(*boundary_face)
+                                .map_or("Not set", |face_bc| {
+                                    face_bc.boundary_condition.get_label()
+                                }),
+                        );
+                    }
+                    ui.end_row();
+                }
+            });
+        ui.horizontal_wrapped(|ui| {
+            let legend = if self.get_coloured_scalar().is_some() {
+                [
+                    scalar::BoundaryConditionGui::AntiBBNoFlux,
+                    scalar::BoundaryConditionGui::ZerothOrderNoFlux,
+                    scalar::BoundaryConditionGui::AntiBounceBack { value: 0.0 },
+                    scalar::BoundaryConditionGui::Periodic,
+                ]
+                .map(|boundary_condition| {
+                    (
+                        get_scalar_face_color(&boundary_condition),
+                        match boundary_condition {
+                            scalar::BoundaryConditionGui::AntiBBNoFlux => "No-flux wall",
+                            scalar::BoundaryConditionGui::ZerothOrderNoFlux => "No-flux outflow",
+                            _ => boundary_condition.get_label(),
+                        },
+                    )
+                })
+            } else {
+                [
+                    momentum::BoundaryConditionGui::NoSlip,
+                    momentum::BoundaryConditionGui::BounceBack {
+                        rho: 1.0,
+                        ux: 0.0,
+                        uy: 0.0,
+                        uz: 0.0,
+                    },
+                    momentum::BoundaryConditionGui::AntiBounceBack { rho: 1.0 },
+                    momentum::BoundaryConditionGui::Periodic,
+                ]
+                .map(|boundary_condition| {
+                    (
+                        get_momentum_face_color(&boundary_condition),
+                        boundary_condition.get_label(),
+                    )
+                })
+            };
+            for (color, label) in legend.into_iter().chain([(NOT_SET_COLOR, "Not set")]) {
+                ui.colored_label(color, "■");
+                ui.label(label);
+            }
+            ui.colored_label(SOLID_COLOR, "■");
+            ui.label("Solid");
+        });
+    }
+
+    pub(super) fn ui_domain_preview(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Domain preview");
+        if !self.s_cfg_vec.is_empty() {
+            ui.horizontal(|ui| {
+                ui.label("Colour faces by:");
+                let coloured_scalar = &mut self.domain_preview.coloured_scalar;
+                ui.selectable_value(coloured_scalar, None, "Momentum");
+                for (i, s_cfg) in self.s_cfg_vec.iter().enumerate() {
+                    ui.selectable_value(coloured_scalar, Some(i), &s_cfg.name);
+                }
+            });
+        }
+        if self
+            .domain_preview
+            .map
+            .as_ref()
+            .is_some_and(|map| map.grid != self.d_cfg.grid)
+            || self.d_cfg.node_type_mask == NodeTypeMaskGui::OnlyFluidNodes
+        {
+            self.domain_preview.map = None;
+        }
+        if self.d_cfg.node_type_mask != NodeTypeMaskGui::OnlyFluidNodes {
+            ui.horizontal(|ui| {
+                if ui.button("Load map").clicked() {
+                    match self.load_node_type_map() {
+                        Ok(map) => {
+                            self.domain_preview.map = Some(map);
+                            self.domain_preview.status = String::new();
+                        }
+                        Err(e) => self.domain_preview.status = format!("Error: {}", e),
+                    }
+                }
+                ui.label(&self.domain_preview.status);
+            });
+        }
+        let n_axes = self.d_cfg.dim.get_n_axes();
+        if self.d_cfg.grid[..n_axes].contains(&0) {
+            ui.colored_label(
+                ui.visuals().error_fg_color,
+                "Every grid extent must be at least 1 node",
+            );
+            return;
+        }
+        match self.d_cfg.dim {
+            Dimensionality::D2 => {
+                self.ui_preview_slice(ui, (0, 1), (2, 0));
+            }
+            Dimensionality::D3 => {
+                ui.horizontal(|ui| {
+                    ui.label("Slice:");
+                    for (axis, axis_name) in AXIS_NAMES.iter().enumerate() {
+                        ui.selectable_value(&mut self.domain_preview.slice_axis, axis, *axis_name);
+                    }
+                    let last_index =
+                        self.d_cfg.grid[self.domain_preview.slice_axis].saturating_sub(1);
+                    self.domain_preview.slice_index =
+                        self.domain_preview.slice_index.min(last_index);
+                    ui.add(egui::Slider::new(
+                        &mut self.domain_preview.slice_index,
+                        0..=last_index,
+                    ));
+                });
+                let slice_axis = self.domain_preview.slice_axis;
+                let slice_axes = match slice_axis {
+                    0 => (1, 2),
+                    1 => (0, 2),
+                    _ => (0, 1),
+                };
+                ui.horizontal(|ui| {
+                    self.ui_preview_isometric(ui);
+                    self.ui_preview_slice(
+                        ui,
+                        slice_axes,
+                        (slice_axis, self.domain_preview.slice_index),
+                    );
+                });
+            }
+        }
+        self.ui_preview_boundary_table(ui);
+    }
+}
@@ -0,0 +1,124 @@
+use eframe::egui;
+use std::time::Duration;
+
+use super::GuiApp;
+use crate::runner::{self, CargoCommand, CargoProcess};
+
+/// Size of the case when a run started, to measure its throughput.
+#[derive(Clone, Copy)]
+pub(crate) struct TimedRun {
+    pub(crate) n_nodes: u64,
+    pub(crate) n_steps: u64,
+}
+
+#[derive(Default)]
+pub(crate) struct RunGui {
+    process: Option<CargoProcess>,
+    timed_run: Option<TimedRun>,
+    status: String,
+}
+
+impl GuiApp {
+    fn start_cargo(&mut self, command: CargoCommand) {
+        let case_dir = self.get_case_dir();
+        if !case_dir.join("Cargo.toml").is_file() {
+            self.run.status = "Build the case first".to_string();
+            return;
+        }
+        match CargoProcess::spawn(command, &case_dir) {
+            Ok(process) => {
+                self.run.timed_run = Some(TimedRun {
+                    n_nodes: self.get_memory_estimate().n_nodes,
+                    n_steps: self.estimator.n_steps,
+                });
+                self.run.process = Some(process);
+                self.run.status = String::new();
+            }
+            Err(e) => self.run.status = format!("Error: {}", e),
+        }
+    }
+
+    /// Size and duration of the case executable in the last successful run
+    /// of this session, without the compilation before it.
+    pub(super) fn get_last_run(&self) -> Option<(TimedRun, Duration)> {
+        let process = self.run.process.as_ref()?;
+        match &process.finished {
+            Some((Ok(status), _)) if status.success() => {
+                Some((self.run.timed_run?, process.get_run_elapsed()?))
+            }
+            _ => None,
+        }
+    }
+
+    pub(super) fn ui_run(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Run");
+        let is_running = self.run.process.as_mut().is_some_and(|process| {
+            process.poll();
+            process.is_running()
+        });
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(!is_running, egui::Button::new("Build"))
+                .clicked()
+            {
+                self.start_cargo(CargoCommand::Build);
+            }
+            if ui
+                .add_enabled(!is_running, egui::Button::new("Run"))
+                .clicked()
+            {
+                self.start_cargo(CargoCommand::Run);
+            }
+            if ui
+                .add_enabled(is_running, egui::Button::new("Stop"))
+                .clicked()
+                && let Some(process) = &mut self.run.process
+                && let Err(e) = process.stop()
+            {
+                self.run.status = format!("Error: {}", e);
+            }
+            if let Some(process) = &self.run.process {
+                let command_literal = process.command.get_label();
+                let elapsed = process.get_elapsed().as_secs_f64();
+                match &process.finished {
+                    None => ui.label(format!("Running {}... {:.1} s", command_literal, elapsed)),
+                    Some((status, _)) => ui.label(format!(
+                        "{}: {} after {:.1} s",
+                        command_literal,
+                        runner::get_status_literal(status),
+                        elapsed
+                    )),
+                };
+                if let Some(run_elapsed) = process.get_run_elapsed() {
+                    ui.label(format!(
+                        "(case executable {:.1} s)",
+                        run_elapsed.as_secs_f64()
+                    ));
+                }
+            }
+            ui.label(&self.run.status);
+        });
+        if let Some(process) = &self.run.process
+            && let Ok(log) = process.log.lock()
+        {
+            let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+            egui::ScrollArea::vertical()
+                .max_height(300.0)
+                .stick_to_bottom(true)
+                .auto_shrink([false, true])
+                .show_rows(ui, row_height, log.len(), |ui, row_range| {
+                    for line in &log[row_range] {
+                        let text = egui::RichText::new(&line.text).monospace();
+                        if line.is_stderr {
+                            ui.label(text.weak());
+                        } else {
+                            ui.label(text);
+                        }
+                    }
+                });
+        }
+        if is_running {
+            ui.ctx().request_repaint_after(Duration::from_millis(100));
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use eframe::egui;
+use serde::{Deserialize, Serialize};
+
+use super::GuiApp;
+use crate::templates::CaseTemplate;
+
+const SESSION_KEY: &str = "session";
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SessionState {
+    case: CaseTemplate,
+    parent_dir: String,
+    has_unsaved_changes: bool,
+}
+
+#[derive(Default)]
+pub(crate) struct SessionGui {
+    /// Configuration as it was last written to a case directory.
+    saved_snapshot: Option<String>,
+    pending_restore: Option<SessionState>,
+    show_close_dialog: bool,
+    allow_close: bool,
+}
+
+impl GuiApp {
+    /// Looks for a session left with unsaved changes by a previous run.
+    pub(super) fn load_session(&mut self, storage: Option<&dyn eframe::Storage>) {
+        self.session.saved_snapshot = self.get_case_snapshot();
+        self.session.pending_restore = storage
+            .and_then(|storage| eframe::get_value::<SessionState>(storage, SESSION_KEY))
+            .filter(|session| session.has_unsaved_changes);
+    }
+
+    pub(super) fn save_session(&mut self, storage: &mut dyn eframe::Storage) {
+        // Keep the previous session until the user decided what to do with it.
+        if let Some(session) = &self.session.pending_restore {
+            eframe::set_value(storage, SESSION_KEY, session);
+            return;
+        }
+        let session = SessionState {
+            case: self.get_case_template(),
+            parent_dir: self.parent_dir.clone(),
+            has_unsaved_changes: self.has_unsaved_changes() && !self.session.allow_close,
+        };
+        eframe::set_value(storage, SESSION_KEY, &session);
+    }
+
+    pub(super) fn mark_saved(&mut self) {
+        self.session.saved_snapshot = self.get_case_snapshot();
+    }
+
+    fn has_unsaved_changes(&self) -> bool {
+        self.get_case_snapshot() != self.session.saved_snapshot
+    }
+
+    fn restore_session(&mut self, session: SessionState) {
+        self.set_case_template(session.case);
+        self.parent_dir = session.parent_dir;
+        self.update_source_code_path();
+        self.status = "Previous session restored".to_string();
+    }
+
+    pub(super) fn ui_restore_session_window(&mut self, ctx: &egui::Context) {
+        if self.session.pending_restore.is_none() {
+            return;
+        }
+        let mut restore = None;
+        egui::Window::new("Restore previous session")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                if let Some(session) = &self.session.pending_restore {
+                    ui.label(format!(
+                        "The previous session ended with unsaved changes to case {}.",
+                        session.case.c_cfg.case_name
+                    ));
+                }
+                ui.horizontal(|ui| {
+                    if ui.button("Restore").clicked() {
+                        restore = Some(true);
+                    }
+                    if ui.button("Discard").clicked() {
+                        restore = Some(false);
+                    }
+                });
+            });
+        match restore {
+            Some(true) => {
+                if let Some(session) = self.session.pending_restore.take() {
+                    self.restore_session(session);
+                }
+            }
+            Some(false) => self.session.pending_restore = None,
+            None => {}
+        }
+    }
+
+    /// Cancels closing the window while there are unsaved changes and asks
+    /// the user to confirm.
+    pub(super) fn ui_close_dialog(&mut self, ctx: &egui::Context) {
+        if ctx.input(|input| input.viewport().close_requested())
+            && !self.session.allow_close
+            && self.has_unsaved_changes()
+        {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            self.session.show_close_dialog = true;
+        }
+        if !self.session.show_close_dialog {
+            return;
+        }
+        egui::Window::new("Unsaved changes")
+            .collapsible(false)
+            .resizable(false)
+            .show(ctx, |ui| {
+                ui.label("The configuration changed since the case was last built.");
+                ui.horizontal(|ui| {
+                    if ui.button("Build and close").clicked() {
+                        self.session.show_close_dialog = false;
+                        self.build_case();
+                        if self.pending_build.is_none() && !self.has_unsaved_changes() {
+                            self.session.allow_close = true;
+                            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                        }
+                    }
+                    if ui.button("Close without building").clicked() {
+                        self.session.allow_close = true;
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    }
+                    if ui.button("Cancel").clicked() {
+                        self.session.show_close_dialog = false;
+                    }
+                });
+            });
+    }
+}
@@ -0,0 +1,209 @@
+use eframe::egui;
+
+use super::GuiApp;
+use super::geometry::GeometryAnalysisGui;
+use super::preview::DomainPreviewGui;
+use crate::templates::{self, BuiltInTemplate, CaseTemplate, UserTemplate};
+
+#[derive(Default)]
+pub(crate) struct TemplateLibraryGui {
+    is_save_window_open: bool,
+    is_library_window_open: bool,
+    name: String,
+    description: String,
+    user_templates: Vec<UserTemplate>,
+    selected: Option<usize>,
+    new_case_name: String,
+    status: String,
+}
+
+impl GuiApp {
+    pub(super) fn get_case_template(&self) -> CaseTemplate {
+        CaseTemplate {
+            d_cfg: self.d_cfg.clone(),
+            m_cfg: self.m_cfg.clone(),
+            s_cfg_vec: self.s_cfg_vec.clone(),
+            c_cfg: self.c_cfg.clone(),
+            j_cfg: self.j_cfg.clone(),
+        }
+    }
+
+    pub(super) fn set_case_template(&mut self, template: CaseTemplate) {
+        self.d_cfg = template.d_cfg;
+        self.m_cfg = template.m_cfg;
+        self.n_scalars = template.s_cfg_vec.len();
+        self.s_cfg_vec = template.s_cfg_vec;
+        self.c_cfg = template.c_cfg;
+        self.j_cfg = template.j_cfg;
+    }
+
+    pub(super) fn apply_template(&mut self, template: CaseTemplate) {
+        self.set_case_template(template);
+        self.geometry_analysis = GeometryAnalysisGui::default();
+        self.domain_preview = DomainPreviewGui::default();
+        self.status = String::new();
+    }
+
+    fn reload_user_templates(&mut self) {
+        let library = &mut self.template_library;
+        library.selected = None;
+        match templates::load_user_templates() {
+            Ok(user_templates) => library.user_templates = user_templates,
+            Err(e) => library.status = format!("Error: {}", e),
+        }
+    }
+
+    pub(super) fn ui_templates_menu(&mut self, ui: &mut egui::Ui) {
+        ui.menu_button("New from template", |ui| {
+            for built_in_template in BuiltInTemplate::ALL {
+                if ui
+                    .button(built_in_template.get_name())
+                    .on_hover_text(built_in_template.get_description())
+                    .clicked()
+                {
+                    self.apply_template(built_in_template.get_template());
+                    self.template_note = format!(
+                        "{}: {}",
+                        built_in_template.get_name(),
+                        built_in_template.get_description()
+                    );
+                    ui.close();
+                }
+            }
+        });
+        ui.menu_button("Templates", |ui| {
+            if ui.button("Save current case as template...").clicked() {
+                self.template_library.is_save_window_open = true;
+                self.template_library.status = String::new();
+                ui.close();
+            }
+            if ui.button("Template library...").clicked() {
+                self.template_library.is_library_window_open = true;
+                self.template_library.status = String::new();
+                self.reload_user_templates();
+                ui.close();
+            }
+        });
+    }
+
+    pub(super) fn ui_template_windows(&mut self, ctx: &egui::Context) {
+        let mut is_save_window_open = self.template_library.is_save_window_open;
+        egui::Window::new("Save as template")
+            .open(&mut is_save_window_open)
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Name:");
+                    ui.text_edit_singleline(&mut self.template_library.name);
+                });
+                ui.label("Description:");
+                ui.text_edit_multiline(&mut self.template_library.description);
+                ui.horizontal(|ui| {
+                    if ui.button("Save").clicked() {
+                        let user_template = UserTemplate {
+                            name: self.template_library.name.trim().to_string(),
+                            description: self.template_library.description.clone(),
+                            case: self.get_case_template(),
+                        };
+                        self.template_library.status = match user_template.save() {
+                            Ok(path) => format!("Template saved to {}", path.display()),
+                            Err(e) => format!("Error: {}", e),
+                        };
+                    }
+                    ui.label(&self.template_library.status);
+                });
+            });
+        self.template_library.is_save_window_open = is_save_window_open;
+
+        let mut is_library_window_open = self.template_library.is_library_window_open;
+        let mut instantiated = None;
+        egui::Window::new("Template library")
+            .open(&mut is_library_window_open)
+            .show(ctx, |ui| {
+                if let Some(dir) = templates::get_user_templates_dir() {
+                    ui.label(format!("Directory: {}", dir.display()));
+                }
+                ui.horizontal(|ui| {
+                    if ui.button("Refresh").clicked() {
+                        self.reload_user_templates();
+                    }
+                    ui.label(&self.template_library.status);
+                });
+                ui.separator();
+                let library = &mut self.template_library;
+                if library.user_templates.is_empty() {
+                    ui.label("No saved templates");
+                }
+                ui.horizontal_top(|ui| {
+                    ui.vertical(|ui| {
+                        for (i, user_template) in library.user_templates.iter().enumerate() {
+                            if ui
+                                .selectable_label(library.selected == Some(i), &user_template.name)
+                                .clicked()
+                            {
+                                library.selected = Some(i);
+                                library.new_case_name = user_template.case.c_cfg.case_name.clone();
+                            }
+                        }
+                    });
+                    let Some(selected) = library.selected else {
+                        return;
+                    };
+                    ui.separator();
+                    ui.vertical(|ui| {
+                        let user_template = &library.user_templates[selected];
+                        ui.strong(&user_template.name);
+                        if !user_template.description.is_empty() {
+                            ui.label(&user_template.description);
+                        }
+                        egui::Grid::new("template_summary_grid")
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for (key, value) in user_template.case.get_summary() {
+                                    ui.label(key);
+                                    ui.label(value);
+                                    ui.end_row();
+                                }
+                            });
+                        ui.add_space(10.0);
+                        ui.horizontal(|ui| {
+                            ui.label("New case name:");
+                            ui.text_edit_singleline(&mut library.new_case_name);
+                        });
+                        let mut should_delete = false;
+                        ui.horizontal(|ui| {
+                            if ui.button("Create case from template").clicked() {
+                                if library.new_case_name.trim().is_empty() {
+                                    library.status = "Case name empty".to_string();
+                                } else {
+                                    instantiated = Some(selected);
+                                }
+                            }
+                            should_delete = ui.button("Delete template").clicked();
+                        });
+                        if should_delete {
+                            let user_template = library.user_templates.remove(selected);
+                            library.status = match user_template.delete() {
+                                Ok(()) => format!("Template {} deleted", user_template.name),
+                                Err(e) => format!("Error: {}", e),
+                            };
+                            library.selected = None;
+                        }
+                    });
+                });
+            });
+        if let Some(selected) = instantiated {
+            let user_template = self.template_library.user_templates[selected].clone();
+            let mut case = user_template.case;
+            case.c_cfg.case_name = self.template_library.new_case_name.trim().to_string();
+            self.apply_template(case);
+            self.template_note = if user_template.description.is_empty() {
+                user_template.name
+            } else {
+                format!("{}: {}", user_template.name, user_template.description)
+            };
+            self.template_library.status =
+                format!("Case {} created from template", self.c_cfg.case_name);
+        }
+        self.template_library.is_library_window_open = is_library_window_open;
+    }
+}
@@ -0,0 +1,297 @@
+use std::path::Path;
+#[cfg(feature = "gui")]
+use std::path::PathBuf;
+
+#[cfg(feature = "gui")]
+use crate::config::CargoGuiConfig;
+use crate::metadata;
+
+pub(crate) enum FileStatus {
+    New,
+    Unchanged,
+    Modified,
+}
+
+/// Compares each file that would be written, given by its path relative to
+/// the case directory, with the file currently on disk. A path that exists
+/// but cannot be read as UTF-8 text is reported as modified, so that it is
+/// never overwritten unnoticed. Metadata files that only differ by their
+/// generation timestamp are unchanged.
+pub(crate) fn compare_with_existing(
+    case_dir: &Path,
+    files: &[(String, String)],
+) -> Vec<(String, FileStatus)> {
+    files
+        .iter()
+        .map(|(file_path, content)| {
+            let path = case_dir.join(file_path);
+            let status = match std::fs::read_to_string(&path) {
+                Err(_) if !path.exists() => FileStatus::New,
+                Err(_) => FileStatus::Modified,
+                Ok(existing)
+                    if existing == *content
+                        || metadata::is_same_apart_from_timestamp(
+                            file_path, &existing, content,
+                        ) =>
+                {
+                    FileStatus::Unchanged
+                }
+                Ok(_) => FileStatus::Modified,
+            };
+            (file_path.clone(), status)
+        })
+        .collect()
+}
+
+/// Unified diff from the file on disk to the generated `content`, or the
+/// read error when the existing file cannot be read as UTF-8 text.
+#[cfg(feature = "gui")]
+pub(crate) fn get_diff(case_dir: &Path, file_path: &str, content: &str) -> String {
+    match std::fs::read_to_string(case_dir.join(file_path)) {
+        Ok(existing) => similar::TextDiff::from_lines(existing.as_str(), content)
+            .unified_diff()
+            .context_radius(2)
+            .header("existing", "generated")
+            .to_string(),
+        Err(e) => format!("The existing file cannot be read: {}\n", e),
+    }
+}
+
+pub(crate) fn write_case_files(case_dir: &Path, files: &[(String, String)]) -> std::io::Result<()> {
+    for (file_path, content) in files {
+        let path = case_dir.join(file_path);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, content)?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "gui")]
+/// Copies the existing files that would be overwritten into a timestamped
+/// `backup_<date>_<time>` folder inside the case directory.
+pub(crate) fn backup_existing_files(
+    case_dir: &Path,
+    files: &[(String, String)],
+) -> std::io::Result<PathBuf> {
+    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+    let backup_dir = case_dir.join(format!("backup_{}", timestamp));
+    for (file_path, _) in files {
+        let path = case_dir.join(file_path);
+        if path.is_file() {
+            let backup_path = backup_dir.join(file_path);
+            if let Some(dir) = backup_path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            std::fs::copy(path, backup_path)?;
+        }
+    }
+    Ok(backup_dir)
+}
+
+#[cfg(feature = "gui")]
+/// Lists the sub-directories of `parent_dir` holding a package manifest,
+/// sorted by name.
+pub(crate) fn get_case_dir_names(parent_dir: &Path) -> Result<Vec<String>, String> {
+    let entries = std::fs::read_dir(parent_dir).map_err(|e| e.to_string())?;
+    let mut members = vec![];
+    for entry in entries.flatten() {
+        let Ok(manifest) = std::fs::read_to_string(entry.path().join("Cargo.toml")) else {
+            continue;
+        };
+        let is_package = manifest
+            .parse::<toml::Table>()
+            .is_ok_and(|table| table.contains_key("package"));
+        if is_package {
+            members.push(entry.file_name().to_string_lossy().into_owned());
+        }
+    }
+    members.sort();
+    Ok(members)
+}
+
+#[cfg(feature = "gui")]
+pub(crate) struct WorkspaceUpdate {
+    pub(crate) members: Vec<String>,
+    pub(crate) release_profile_differs: bool,
+}
+
+#[cfg(feature = "gui")]
+impl WorkspaceUpdate {
+    pub(crate) fn get_summary(&self) -> String {
+        let mut summary = format!("workspace has {} member(s)", self.members.len());
+        if self.release_profile_differs {
+            summary.push_str(
+                ", its [profile.release] differs from the release profile of this case \
+                 and was kept",
+            );
+        }
+        summary
+    }
+}
+
+#[cfg(feature = "gui")]
+fn get_members_array(members: &[String]) -> toml_edit::Array {
+    let mut array = toml_edit::Array::new();
+    for member in members {
+        let mut value = toml_edit::Value::from(member.as_str());
+        value.decor_mut().set_prefix("\n    ");
+        array.push_formatted(value);
+    }
+    array.set_trailing("\n");
+    array.set_trailing_comma(!members.is_empty());
+    array
+}
+
+#[cfg(feature = "gui")]
+/// Writes the workspace Cargo.toml of `parent_dir` listing every case found
+/// in it. A new manifest takes the release profile of `c_cfg`; an existing
+/// one only gets its members updated, keeping its comments and layout, and
+/// the update reports whether its release profile differs from the one of
+/// `c_cfg`. Refuses to replace a manifest that is not a workspace.
+pub(crate) fn update_workspace(
+    parent_dir: &Path,
+    c_cfg: &CargoGuiConfig,
+) -> Result<WorkspaceUpdate, String> {
+    let path = parent_dir.join("Cargo.toml");
+    let members = get_case_dir_names(parent_dir)?;
+    let generated = c_cfg.get_workspace_cargo_toml(&members);
+    if !path.exists() {
+        std::fs::write(&path, generated).map_err(|e| e.to_string())?;
+        return Ok(WorkspaceUpdate {
+            members,
+            release_profile_differs: false,
+        });
+    }
+    let existing = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Could not read {}: {}", path.display(), e))?;
+    let mut document = existing
+        .parse::<toml_edit::DocumentMut>()
+        .map_err(|e| format!("Could not parse {}: {}", path.display(), e))?;
+    let Some(workspace) = document
+        .get_mut("workspace")
+        .and_then(|workspace| workspace.as_table_like_mut())
+    else {
+        return Err(format!("{} is not a workspace manifest", path.display()));
+    };
+    let members_array = get_members_array(&members);
+    match workspace
+        .get_mut("members")
+        .and_then(|item| item.as_value_mut())
+    {
+        Some(value) => {
+            let decor = value.decor().clone();
+            *value = toml_edit::Value::Array(members_array);
+            *value.decor_mut() = decor;
+        }
+        None => {
+            workspace.insert("members", toml_edit::value(members_array));
+        }
+    }
+    let get_release_profile = |content: &str| {
+        content
+            .parse::<toml::Table>()
+            .ok()
+            .and_then(|table| table.get("profile")?.get("release").cloned())
+    };
+    let release_profile_differs = get_release_profile(&existing) != get_release_profile(&generated);
+    std::fs::write(&path, document.to_string()).map_err(|e| e.to_string())?;
+    Ok(WorkspaceUpdate {
+        members,
+        release_profile_differs,
+    })
+}
+
+#[cfg(all(test, feature = "gui"))]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::update_workspace;
+    use crate::config::CargoGuiConfig;
+
+    fn get_parent_dir(name: &str, case_names: &[&str]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("writer_test_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        for case_name in case_names {
+            std::fs::create_dir_all(dir.join(case_name)).unwrap();
+            std::fs::write(
+                dir.join(case_name).join("Cargo.toml"),
+                format!("[package]\nname = \"{}\"\n", case_name),
+            )
+            .unwrap();
+        }
+        std::fs::create_dir_all(dir.join("not_a_case")).unwrap();
+        dir
+    }
+
+    fn get_members(dir: &Path) -> Vec<String> {
+        let table = std::fs::read_to_string(dir.join("Cargo.toml"))
+            .unwrap()
+            .parse::<toml::Table>()
+            .unwrap();
+        table["workspace"]["members"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|member| member.as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn creates_a_workspace_manifest() {
+        let dir = get_parent_dir("create", &["case_b", "case_a"]);
+        let update = update_workspace(&dir, &CargoGuiConfig::default()).unwrap();
+        assert_eq!(update.members, ["case_a", "case_b"]);
+        assert!(!update.release_profile_differs);
+        assert_eq!(get_members(&dir), ["case_a", "case_b"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn updates_members_and_keeps_the_rest() {
+        let dir = get_parent_dir("update", &["case_a", "case_b"]);
+        let existing = "# Shared workspace\n[workspace]\nresolver = \"3\" # keep\n\
+                        members = [\"old_case\"]\nexclude = [\"scratch\"]\n\n\
+                        [profile.release]\nopt-level = 2\n";
+        std::fs::write(dir.join("Cargo.toml"), existing).unwrap();
+        let update = update_workspace(&dir, &CargoGuiConfig::default()).unwrap();
+        assert!(update.release_profile_differs);
+        let content = std::fs::read_to_string(dir.join("Cargo.toml")).unwrap();
+        assert_eq!(
+            content,
+            "# Shared workspace\n[workspace]\nresolver = \"3\" # keep\n\
+             members = [\n    \"case_a\",\n    \"case_b\",\n]\nexclude = [\"scratch\"]\n\n\
+             [profile.release]\nopt-level = 2\n"
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn adds_members_to_a_workspace_without_them() {
+        let dir = get_parent_dir("add", &["case_a"]);
+        std::fs::write(dir.join("Cargo.toml"), "[workspace]\nresolver = \"3\"\n").unwrap();
+        update_workspace(&dir, &CargoGuiConfig::default()).unwrap();
+        assert_eq!(get_members(&dir), ["case_a"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn refuses_to_replace_other_manifests() {
+        let dir = get_parent_dir("refuse", &["case_a"]);
+        let package = "[package]\nname = \"parent\"\n";
+        std::fs::write(dir.join("Cargo.toml"), package).unwrap();
+        let error = update_workspace(&dir, &CargoGuiConfig::default())
+            .err()
+            .unwrap();
+        assert!(error.ends_with("is not a workspace manifest"));
+        let unreadable = [0xff, 0xfe, 0x00];
+        std::fs::write(dir.join("Cargo.toml"), unreadable).unwrap();
+        let error = update_workspace(&dir, &CargoGuiConfig::default())
+            .err()
+            .unwrap();
+        assert!(error.starts_with("Could not read"));
+        assert_eq!(std::fs::read(dir.join("Cargo.toml")).unwrap(), unreadable);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}